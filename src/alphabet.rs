@@ -0,0 +1,121 @@
+use caseless::Caseless;
+use serde::{Deserialize, Serialize};
+use std::iter;
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+/// The languages there is a built-in alphabet for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+	English,
+	German,
+	Spanish,
+	Greek,
+	Cyrillic,
+}
+
+impl Language {
+	/// All letters of the language in their usual order, in lowercase
+	pub fn letters(&self) -> &'static str {
+		match self {
+			Language::English => "abcdefghijklmnopqrstuvwxyz",
+			Language::German => "abcdefghijklmnopqrstuvwxyzäöüß",
+			Language::Spanish => "abcdefghijklmnñopqrstuvwxyzáéíóúü",
+			Language::Greek => "αβγδεζηθικλμνξοπρστυφχψωάέήίόύώϊϋΐΰ",
+			Language::Cyrillic => "абвгдеёжзийклмнопрстуфхцчшщъыьэюя",
+		}
+	}
+}
+
+crate::named_enum!(Language, "alphabet", {
+	English => "english",
+	German => "german",
+	Spanish => "spanish",
+	Greek => "greek",
+	Cyrillic => "cyrillic",
+});
+
+/// Decides which characters of a word are letters that have to be guessed,
+/// and which guesses count as the same letter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Alphabet {
+	pub language: Language,
+	/// Whether letters with accents are matched by the letter without them,
+	/// e.g. guessing "e" reveals "é"
+	pub fold_accents: bool,
+}
+
+impl Default for Alphabet {
+	fn default() -> Self {
+		Alphabet {
+			language: Language::English,
+			fold_accents: false,
+		}
+	}
+}
+
+impl Alphabet {
+	/// The form a letter is compared by, or `None` if the character is not a
+	/// letter at all (e.g. spaces and punctuation).
+	pub fn letter_key(&self, c: char) -> Option<char> {
+		if !c.is_alphabetic() {
+			return None;
+		}
+
+		let folded = fold_case(c);
+		if self.fold_accents {
+			let base = strip_accents(folded);
+			if self.contains(base) {
+				return Some(base);
+			}
+		}
+		Some(folded)
+	}
+
+	/// Whether a letter key (see [Alphabet::letter_key]) is part of the alphabet
+	pub fn contains(&self, key: char) -> bool {
+		self.language.letters().contains(key)
+	}
+
+	/// Whether every letter of the word can be guessed with this alphabet
+	pub fn can_spell(&self, word: &str) -> bool {
+		word.chars()
+			.filter_map(|c| self.letter_key(c))
+			.all(|key| self.contains(key))
+	}
+
+	/// All letter keys of a phrase in order, leaving out everything that isn't
+	/// a letter
+	pub fn letter_keys(&self, phrase: &str) -> String {
+		phrase.chars().filter_map(|c| self.letter_key(c)).collect()
+	}
+}
+
+/// Unicode case folding of a single character. Letters that would fold into
+/// several characters, like "ß" into "ss", are only lowercased.
+fn fold_case(c: char) -> char {
+	let mut folded = iter::once(c).default_case_fold();
+	match (folded.next(), folded.next()) {
+		(Some(single), None) => single,
+		_ => c.to_lowercase().next().unwrap_or(c),
+	}
+}
+
+/// Removes accents and other marks from a letter, e.g. "é" becomes "e"
+fn strip_accents(c: char) -> char {
+	let mut base = iter::once(c).nfd().filter(|c| !is_combining_mark(*c));
+	match (base.next(), base.next()) {
+		(Some(single), None) => single,
+		_ => c,
+	}
+}
+
+/// How a letter key is shown to players
+pub fn display_letter(key: char) -> String {
+	let mut upper = key.to_uppercase();
+	match (upper.next(), upper.next()) {
+		(Some(single), None) => single.to_string(),
+		// e.g. "ß" has no single uppercase letter
+		_ => key.to_string(),
+	}
+}
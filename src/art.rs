@@ -1,27 +1,451 @@
-pub const THE_HANGED_MAN: &str = r#"
-   .---------------.
-  |,      XII      c|
-  |9.=,=.==c=\==,=e8|
-  |bß "  `, \c7    ß|
-  |ß     .| ++´     |
-  |       | ##      |
-  |  (####=~##~~c7  |
-  |    \#\~/##      |
-  |     YYYYYY      |
-  |  ,-´/####\`-,   |
-  | (__T######T__)  |
-  |    `. )( .´     |
-  |   .. (**) ..    |
-  |   .´ ."". `.    |
-  | THE HANGED MAN. |
-   `---------------´
-
-       You lost.
-
-"#;
-
-pub const YOU_WON: &str = r#"
-
-        You won!
-
-"#;
+use crossterm::style::{Attribute, Color, ContentStyle, Stylize};
+use hangman::storage::data_dir;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::PathBuf;
+
+/// The theme that is used when no other one is picked
+pub const DEFAULT_THEME: &str = "classic";
+
+/// The themes that are bundled with the game, by name
+const BUILTIN_THEMES: [(&str, &str); 3] = [
+	("classic", include_str!("../assets/themes/classic.theme")),
+	("snowman", include_str!("../assets/themes/snowman.theme")),
+	("minimal", include_str!("../assets/themes/minimal.theme")),
+];
+
+/// The styles text is shown in, wherever the game uses colours
+#[derive(Debug, Clone, Copy)]
+pub struct Palette {
+	/// Guessed letters that are part of the word
+	pub correct: ContentStyle,
+	/// Guessed letters that are not part of the word
+	pub missed: ContentStyle,
+	/// Letters that were revealed by hints
+	pub hinted: ContentStyle,
+	/// Good news, like a correct guess
+	pub good: ContentStyle,
+	/// Bad news, like a wrong guess
+	pub bad: ContentStyle,
+}
+
+impl Default for Palette {
+	fn default() -> Self {
+		Palette {
+			correct: ContentStyle::new().dark_cyan(),
+			missed: ContentStyle::new().dim(),
+			hinted: ContentStyle::new().dark_yellow(),
+			good: ContentStyle::new().dark_green().bold(),
+			bad: ContentStyle::new().dark_red().bold(),
+		}
+	}
+}
+
+/// Everything the game draws
+#[derive(Debug, Clone)]
+pub struct Theme {
+	/// Drawn one after the other while wrong guesses are made, the last one
+	/// right before the round is lost
+	pub stages: Vec<String>,
+	pub won: String,
+	pub lost: String,
+	pub palette: Palette,
+}
+
+impl Theme {
+	/// The stage for that many wrong guesses. The stages are spread over all
+	/// allowed wrong guesses, so the drawing works for any number of them.
+	pub fn stage(&self, wrong_guesses: usize, max_wrong_guesses: u8) -> &str {
+		let guesses_to_lose = usize::from(max_wrong_guesses) + 1;
+		let wrong_guesses = wrong_guesses.min(guesses_to_lose);
+		// rounding up, so that every wrong guess moves on at least one stage
+		// when there are more stages than guesses
+		let index = (wrong_guesses * (self.stages.len() - 1)).div_ceil(guesses_to_lose);
+		&self.stages[index]
+	}
+}
+
+/// Occurs when an art theme can't be found or doesn't follow the theme format
+#[derive(Debug)]
+pub enum ThemeError {
+	/// There is neither a theme file nor a built-in theme of that name
+	NotFound {
+		name: String,
+		/// Where the theme file would have been, `None` without a data directory
+		path: Option<PathBuf>,
+	},
+
+	/// Theme names may only contain letters, digits, `-` and `_`
+	BadName(String),
+
+	Unreadable {
+		source: String,
+		error: io::Error,
+	},
+
+	/// A `[section]` header the format doesn't know
+	UnknownSection {
+		source: String,
+		line: usize,
+		section: String,
+	},
+
+	/// There is content before the first section header
+	OutsideSection {
+		source: String,
+		line: usize,
+	},
+
+	/// The `[won]`, `[lost]` or `[palette]` section appears twice
+	DuplicateSection {
+		source: String,
+		line: usize,
+		section: String,
+	},
+
+	/// A required section is missing or empty
+	MissingSection {
+		source: String,
+		section: &'static str,
+	},
+
+	/// The palette line is not of the form `name = style`
+	BadPaletteLine {
+		source: String,
+		line: usize,
+		content: String,
+	},
+
+	UnknownPaletteName {
+		source: String,
+		line: usize,
+		name: String,
+	},
+
+	/// A word of a palette style is neither a colour nor an attribute
+	BadStyle {
+		source: String,
+		line: usize,
+		style: String,
+	},
+}
+
+impl Error for ThemeError {}
+
+impl Display for ThemeError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			ThemeError::NotFound { name, path } => {
+				write!(f, "There is no built-in theme \"{}\"", name)?;
+				match path {
+					Some(path) => write!(f, ", and no theme file at {}", path.display()),
+					None => Ok(()),
+				}
+			}
+			ThemeError::BadName(name) => write!(
+				f,
+				"\"{}\" is not a theme name, use only letters, digits, - and _",
+				name
+			),
+			ThemeError::Unreadable { source, error } => {
+				write!(f, "Could not read theme {}: {}", source, error)
+			}
+			ThemeError::UnknownSection {
+				source,
+				line,
+				section,
+			} => write!(
+				f,
+				"{}:{}: unknown section \"{}\", expected [stage], [won], [lost] or [palette]",
+				source, line, section
+			),
+			ThemeError::OutsideSection { source, line } => write!(
+				f,
+				"{}:{}: expected a section header like [stage] before any art",
+				source, line
+			),
+			ThemeError::DuplicateSection {
+				source,
+				line,
+				section,
+			} => write!(
+				f,
+				"{}:{}: the {} section can only appear once",
+				source, line, section
+			),
+			ThemeError::MissingSection { source, section } => {
+				write!(
+					f,
+					"{}: the theme needs a non-empty {} section",
+					source, section
+				)
+			}
+			ThemeError::BadPaletteLine {
+				source,
+				line,
+				content,
+			} => write!(
+				f,
+				"{}:{}: expected a line of the form \"name = style\", found \"{}\"",
+				source, line, content
+			),
+			ThemeError::UnknownPaletteName { source, line, name } => write!(
+				f,
+				"{}:{}: unknown palette name \"{}\", expected correct, missed, hinted, good or bad",
+				source, line, name
+			),
+			ThemeError::BadStyle {
+				source,
+				line,
+				style,
+			} => write!(
+				f,
+				"{}:{}: \"{}\" is neither a colour (like dark_cyan) nor an attribute (like bold)",
+				source, line, style
+			),
+		}
+	}
+}
+
+/// The part of a theme file that is being read
+enum Section {
+	Stage,
+	Won,
+	Lost,
+	Palette,
+}
+
+/// Parses a theme, made of sections that each start with a header line:
+///
+/// - `[stage]`, any number of times, the art drawn after the wrong guesses
+/// - `[won]` and `[lost]`, the art at the end of a round
+/// - `[palette]`, optional, lines of the form `name = style`, where the name
+///   is one of `correct`, `missed`, `hinted`, `good` and `bad`, and the style
+///   is a colour like `dark_cyan`, attributes like `bold`, or both
+///
+/// Lines starting with `# ` are comments, empty lines at the end of a
+/// section are ignored.
+///
+/// `source` is the name the theme is referred to by in errors, e.g. its path.
+pub fn parse_theme(source: &str, contents: &str) -> Result<Theme, ThemeError> {
+	let mut stages: Vec<Vec<&str>> = Vec::new();
+	let mut won: Option<Vec<&str>> = None;
+	let mut lost: Option<Vec<&str>> = None;
+	let mut palette: Option<Palette> = None;
+	let mut section: Option<Section> = None;
+
+	for (index, line) in contents.lines().enumerate() {
+		let line_number = index + 1;
+		let line = line.trim_end_matches('\r');
+		if line.starts_with("# ") {
+			continue;
+		}
+
+		if let Some(name) = section_header(line) {
+			let duplicate = match name {
+				"stage" => {
+					stages.push(Vec::new());
+					section = Some(Section::Stage);
+					false
+				}
+				"won" => {
+					section = Some(Section::Won);
+					won.replace(Vec::new()).is_some()
+				}
+				"lost" => {
+					section = Some(Section::Lost);
+					lost.replace(Vec::new()).is_some()
+				}
+				"palette" => {
+					section = Some(Section::Palette);
+					palette.replace(Palette::default()).is_some()
+				}
+				_ => {
+					return Err(ThemeError::UnknownSection {
+						source: source.to_string(),
+						line: line_number,
+						section: name.to_string(),
+					})
+				}
+			};
+			if duplicate {
+				return Err(ThemeError::DuplicateSection {
+					source: source.to_string(),
+					line: line_number,
+					section: line.trim().to_string(),
+				});
+			}
+			continue;
+		}
+
+		let art = match section {
+			None if line.trim().is_empty() => continue,
+			None => {
+				return Err(ThemeError::OutsideSection {
+					source: source.to_string(),
+					line: line_number,
+				})
+			}
+			Some(Section::Stage) => stages.last_mut(),
+			Some(Section::Won) => won.as_mut(),
+			Some(Section::Lost) => lost.as_mut(),
+			Some(Section::Palette) => {
+				if let Some(palette) = palette.as_mut() {
+					parse_palette_line(palette, source, line_number, line)?;
+				}
+				continue;
+			}
+		};
+		if let Some(art) = art {
+			art.push(line);
+		}
+	}
+
+	let missing = |section: &'static str| ThemeError::MissingSection {
+		source: source.to_string(),
+		section,
+	};
+	let stages: Vec<String> = stages.iter().map(|lines| join_art(lines)).collect();
+	if stages.is_empty() {
+		return Err(missing("[stage]"));
+	}
+	let won = won.map(|lines| join_art(&lines)).unwrap_or_default();
+	if won.trim().is_empty() {
+		return Err(missing("[won]"));
+	}
+	let lost = lost.map(|lines| join_art(&lines)).unwrap_or_default();
+	if lost.trim().is_empty() {
+		return Err(missing("[lost]"));
+	}
+
+	Ok(Theme {
+		stages,
+		won,
+		lost,
+		palette: palette.unwrap_or_default(),
+	})
+}
+
+/// The name of a section if the line is a header like `[stage]`
+fn section_header(line: &str) -> Option<&str> {
+	let name = line.trim().strip_prefix('[')?.strip_suffix(']')?;
+	if !name.is_empty() && name.chars().all(|c| c.is_ascii_lowercase()) {
+		Some(name)
+	} else {
+		None
+	}
+}
+
+/// The lines of a section, without the empty lines that separate it from
+/// the next one
+fn join_art(lines: &[&str]) -> String {
+	let end = lines
+		.iter()
+		.rposition(|line| !line.trim().is_empty())
+		.map_or(0, |last| last + 1);
+	lines[..end].join("\n")
+}
+
+fn parse_palette_line(
+	palette: &mut Palette,
+	source: &str,
+	line_number: usize,
+	line: &str,
+) -> Result<(), ThemeError> {
+	if line.trim().is_empty() {
+		return Ok(());
+	}
+
+	let (name, style) = match line.split_once('=') {
+		Some((name, style)) if !style.trim().is_empty() => (name.trim(), style),
+		_ => {
+			return Err(ThemeError::BadPaletteLine {
+				source: source.to_string(),
+				line: line_number,
+				content: line.to_string(),
+			})
+		}
+	};
+
+	let target = match name {
+		"correct" => &mut palette.correct,
+		"missed" => &mut palette.missed,
+		"hinted" => &mut palette.hinted,
+		"good" => &mut palette.good,
+		"bad" => &mut palette.bad,
+		_ => {
+			return Err(ThemeError::UnknownPaletteName {
+				source: source.to_string(),
+				line: line_number,
+				name: name.to_string(),
+			})
+		}
+	};
+
+	let mut content_style = ContentStyle::new();
+	for word in style.split_whitespace() {
+		match (Color::try_from(word), parse_attribute(word)) {
+			(Ok(color), _) => content_style.foreground_color = Some(color),
+			(_, Some(attribute)) => content_style.attributes.set(attribute),
+			_ => {
+				return Err(ThemeError::BadStyle {
+					source: source.to_string(),
+					line: line_number,
+					style: word.to_string(),
+				})
+			}
+		}
+	}
+	*target = content_style;
+	Ok(())
+}
+
+fn parse_attribute(name: &str) -> Option<Attribute> {
+	match name {
+		"bold" => Some(Attribute::Bold),
+		"dim" => Some(Attribute::Dim),
+		"italic" => Some(Attribute::Italic),
+		"underlined" => Some(Attribute::Underlined),
+		"reverse" => Some(Attribute::Reverse),
+		_ => None,
+	}
+}
+
+/// Where players can put their own themes, e.g.
+/// `~/.local/share/hangman/themes`
+pub fn themes_dir() -> Option<PathBuf> {
+	data_dir().ok().map(|dir| dir.join("themes"))
+}
+
+/// Loads the theme `<name>.theme` from the [themes_dir], or the built-in
+/// theme of that name if there is no such file
+pub fn load_theme(name: &str) -> Result<Theme, ThemeError> {
+	let is_valid = !name.is_empty()
+		&& name
+			.chars()
+			.all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+	if !is_valid {
+		return Err(ThemeError::BadName(name.to_string()));
+	}
+
+	let path = themes_dir().map(|dir| dir.join(format!("{}.theme", name)));
+	if let Some(path) = &path {
+		let source = path.display().to_string();
+		match fs::read_to_string(path) {
+			Ok(contents) => return parse_theme(&source, &contents),
+			Err(error) if error.kind() == ErrorKind::NotFound => (),
+			Err(error) => return Err(ThemeError::Unreadable { source, error }),
+		}
+	}
+
+	match BUILTIN_THEMES.iter().find(|(builtin, _)| *builtin == name) {
+		Some((builtin, contents)) => parse_theme(&format!("built-in theme {}", builtin), contents),
+		None => Err(ThemeError::NotFound {
+			name: name.to_string(),
+			path,
+		}),
+	}
+}
@@ -0,0 +1,118 @@
+use hangman::alphabet::display_letter;
+use hangman::driver::Frontend;
+use hangman::game_state::{EndFeedback, GameAction, GameScene, GameState, GuessFeedback};
+use hangman::solver::{Move, Solver};
+use std::convert::Infallible;
+
+/// How the solver did over all rounds
+#[derive(Debug, Default)]
+pub struct SolverStats {
+	pub rounds: u32,
+	pub won: u32,
+	pub guesses: usize,
+	pub wrong_guesses: usize,
+}
+
+impl SolverStats {
+	pub fn print(&self) {
+		let per_round = |total: usize| total as f64 / f64::from(self.rounds.max(1));
+		println!("Rounds played:  {}", self.rounds);
+		println!(
+			"Rounds won:     {} ({:.0}%)",
+			self.won,
+			100.0 * f64::from(self.won) / f64::from(self.rounds.max(1))
+		);
+		println!("Guesses:        {:.1} per round", per_round(self.guesses));
+		println!(
+			"Wrong guesses:  {:.1} per round",
+			per_round(self.wrong_guesses)
+		);
+	}
+}
+
+/// Lets a [Solver] play random rounds, and prints every guess with the reason
+/// for it
+pub struct AutoplayFrontend {
+	pub solver: Solver,
+	/// How many rounds to play before the game ends
+	pub rounds: u32,
+	/// Whether every guess is shown, or just the results
+	pub verbose: bool,
+	pub stats: SolverStats,
+}
+
+impl Frontend for AutoplayFrontend {
+	type Error = Infallible;
+
+	fn render(&mut self, state: &GameState) -> Result<(), Infallible> {
+		match &state.scene {
+			GameScene::AwaitingGuess { round, feedback } if self.verbose => {
+				let word = round.masked_word();
+				match feedback {
+					GuessFeedback::LetsGo => {
+						println!("Round {}:  {}", state.rounds_played, word);
+					}
+					GuessFeedback::Correct(_) => println!("{:>8}  {}", "", word),
+					_ => println!(
+						"{:>8}  {}  ({} of {} wrong guesses)",
+						"",
+						word,
+						round.wrong_guesses(),
+						state.settings.max_wrong_guesses
+					),
+				}
+			}
+			GameScene::RoundEnd { round, won, .. } => {
+				self.stats.rounds += 1;
+				self.stats.won += u32::from(*won);
+				self.stats.guesses += round.history.len();
+				self.stats.wrong_guesses += round.wrong_guesses();
+				if self.verbose {
+					let result = if *won { "Solved" } else { "Lost" };
+					println!(
+						"{:>8}  {}: {} with {} wrong guesses",
+						"",
+						result,
+						round.word,
+						round.wrong_guesses()
+					);
+					println!();
+				}
+			}
+			_ => (),
+		}
+		Ok(())
+	}
+
+	fn next_action(&mut self, state: &GameState) -> Result<GameAction, Infallible> {
+		Ok(match &state.scene {
+			GameScene::Init | GameScene::RoundEnd { .. } if state.rounds_played >= self.rounds => {
+				GameAction::EndGame(EndFeedback::ManuallyEnded)
+			}
+			GameScene::Init => GameAction::StartRandomGame,
+			GameScene::RoundEnd { .. } => GameAction::NewRound,
+			GameScene::AwaitingGuess { round, .. } => match self.solver.next_move(round) {
+				Some(next) => {
+					if self.verbose {
+						let shown = match &next {
+							Move::Guess { letter, .. } => display_letter(*letter),
+							Move::Solve { phrase, .. } => phrase.clone(),
+						};
+						println!("{:>8}  {}: {}", "", shown, next.reason());
+					}
+					next.action()
+				}
+				// losing the round is all that's left
+				None => {
+					if self.verbose {
+						println!("{:>8}  Out of ideas, giving up", "");
+					}
+					GameAction::GiveUp
+				}
+			},
+			GameScene::ValidGuess { .. } => GameAction::MakeGuess,
+			GameScene::ValidSolve { .. } => GameAction::MakeSolve,
+			GameScene::GameEnd { .. } => unreachable!("the game is already over"),
+		})
+	}
+}
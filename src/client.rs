@@ -0,0 +1,278 @@
+use crate::input::{is_interrupted, is_outdated, is_terminal_closed, UPDATE_PENDING};
+use crate::render::TerminalFrontend;
+use crossterm::style::Stylize;
+use hangman::driver::Frontend;
+use hangman::errors::NetError;
+use hangman::game_state::{GameAction, GameScene, GameState};
+use hangman::net::{self, ClientMessage, HostMessage, NetMode, NetPlayer, DEFAULT_PORT};
+use std::error::Error;
+use std::io::BufReader;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::atomic::Ordering;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+/// What the host last sent about the game
+struct View {
+	state: GameState,
+	players: Vec<NetPlayer>,
+	last_player: Option<String>,
+}
+
+/// A connection to the host of a networked game
+pub struct Connection {
+	stream: TcpStream,
+	/// Everything the host sends, read on a thread of its own so it can
+	/// arrive while the player is typing
+	messages: Receiver<Result<HostMessage, NetError>>,
+	pub mode: NetMode,
+}
+
+impl Connection {
+	/// Connects to the host and joins its game under the player's name. The
+	/// port can be left out of the address, [DEFAULT_PORT] is used then.
+	pub fn join(address: &str, name: &str) -> Result<Connection, NetError> {
+		let stream = if address.to_socket_addrs().is_ok() {
+			TcpStream::connect(address)
+		} else {
+			TcpStream::connect((address, DEFAULT_PORT))
+		}
+		.map_err(NetError::Io)?;
+		let mut reader = BufReader::new(stream.try_clone().map_err(NetError::Io)?);
+
+		let mut stream = stream;
+		net::send(
+			&mut stream,
+			&ClientMessage::Join {
+				name: name.to_string(),
+			},
+		)?;
+		let mode = match net::receive(&mut reader)? {
+			Some(HostMessage::Welcome { mode }) => mode,
+			Some(HostMessage::Rejected { reason }) => return Err(NetError::Rejected(reason)),
+			Some(message) => return Err(NetError::Unexpected(format!("{:?}", message))),
+			None => return Err(NetError::Disconnected),
+		};
+
+		let (sender, messages) = mpsc::channel();
+		thread::spawn(move || loop {
+			let message =
+				net::receive(&mut reader).and_then(|message| message.ok_or(NetError::Disconnected));
+			let failed = message.is_err();
+			if sender.send(message).is_err() || failed {
+				return;
+			}
+			UPDATE_PENDING.store(true, Ordering::SeqCst);
+		});
+
+		Ok(Connection {
+			stream,
+			messages,
+			mode,
+		})
+	}
+
+	fn send(&mut self, message: &ClientMessage) -> Result<(), NetError> {
+		net::send(&mut self.stream, message)
+	}
+
+	/// Waits for the next update of the game
+	fn next_update(&self) -> Result<View, NetError> {
+		loop {
+			let message = self.messages.recv().map_err(|_| NetError::Disconnected)??;
+			if let Some(view) = to_view(message) {
+				return Ok(view);
+			}
+		}
+	}
+
+	/// The last of the updates that arrived in the meantime, if there were any
+	fn latest_update(&self) -> Result<Option<View>, NetError> {
+		let mut latest = None;
+		loop {
+			match self.messages.try_recv() {
+				Ok(message) => {
+					if let Some(view) = to_view(message?) {
+						latest = Some(view);
+					}
+				}
+				Err(TryRecvError::Empty) => return Ok(latest),
+				Err(TryRecvError::Disconnected) => return Err(NetError::Disconnected),
+			}
+		}
+	}
+}
+
+fn to_view(message: HostMessage) -> Option<View> {
+	match message {
+		HostMessage::Update {
+			state,
+			players,
+			last_player,
+		} => Some(View {
+			state: *state,
+			players,
+			last_player,
+		}),
+		// the update that always follows shows how the game is now, e.g.
+		// after another player was quicker
+		HostMessage::Rejected { .. } => None,
+		HostMessage::Welcome { .. } => None,
+	}
+}
+
+/// Plays the game of the host until it's over, or the player leaves it
+pub fn play(
+	mut connection: Connection,
+	mut frontend: TerminalFrontend,
+) -> Result<(), Box<dyn Error>> {
+	let mut view = connection.next_update()?;
+	let mut changed = true;
+
+	loop {
+		UPDATE_PENDING.store(false, Ordering::SeqCst);
+		if let Some(update) = connection.latest_update()? {
+			view = update;
+			changed = true;
+		}
+		if changed {
+			frontend.render(&view.state)?;
+			print_players(&view, connection.mode);
+			changed = false;
+		}
+		if view.state.is_over() {
+			connection.send(&ClientMessage::Leave)?;
+			return Ok(());
+		}
+
+		match frontend.read_action(&view.state) {
+			// leaving only ends the game for this player, everybody else
+			// can go on
+			Ok(GameAction::EndGame(feedback)) => {
+				connection.send(&ClientMessage::Leave)?;
+				frontend.render(&view.state.end_game(feedback))?;
+				return Ok(());
+			}
+			Ok(action) => {
+				connection.send(&ClientMessage::Action { action })?;
+				view = connection.next_update()?;
+				changed = true;
+			}
+			// somebody else changed the game, it's shown again
+			Err(error) if is_outdated(&error) => (),
+			Err(error) if is_interrupted(&error) || is_terminal_closed(&error) => {
+				connection.send(&ClientMessage::Leave)?;
+				return Ok(());
+			}
+			Err(error) => return Err(error.into()),
+		}
+	}
+}
+
+/// Shows who else is playing: their names in co-op games, where the last
+/// move might have been somebody else's, and how everybody is doing in races
+fn print_players(view: &View, mode: NetMode) {
+	println!();
+	match mode {
+		NetMode::Coop => {
+			let names: Vec<&str> = view
+				.players
+				.iter()
+				.map(|player| player.name.as_str())
+				.collect();
+			println!("Playing together: {}", names.join(", "));
+			if let (Some(name), GameScene::AwaitingGuess { .. } | GameScene::RoundEnd { .. }) =
+				(&view.last_player, &view.state.scene)
+			{
+				println!("Last move by {}", name);
+			}
+		}
+		NetMode::Race => {
+			println!(
+				"{}",
+				format!(
+					"{:>4}  {:<16} {:>7} {:>7}",
+					"#", "Player", "Rounds", "Score"
+				)
+				.bold()
+			);
+			for (place, player) in view.players.iter().enumerate() {
+				println!(
+					"{:>4}  {:<16} {:>7} {:>7.0}",
+					place + 1,
+					player.name,
+					player.rounds_played,
+					player.score
+				);
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::host::serve;
+	use hangman::game_state::GameSettings;
+	use hangman::net::HostGame;
+	use hangman::word_list::WordListEntry;
+	use std::net::TcpListener;
+
+	fn host_on_loopback(words: &[&str]) -> String {
+		let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+		let address = listener.local_addr().unwrap().to_string();
+		let words = words
+			.iter()
+			.map(|phrase| WordListEntry::new(phrase, 50))
+			.collect();
+		let game = HostGame::new(NetMode::Coop, GameSettings::default(), words);
+		thread::spawn(move || serve(listener, game));
+		address
+	}
+
+	#[test]
+	fn plays_a_round_with_the_host() {
+		let address = host_on_loopback(&["cat"]);
+		let mut connection = Connection::join(&address, "Alice").unwrap();
+		assert_eq!(connection.mode, NetMode::Coop);
+		assert!(matches!(
+			connection.next_update().unwrap().state.scene,
+			GameScene::Init
+		));
+
+		let mut play = |action| {
+			connection.send(&ClientMessage::Action { action }).unwrap();
+			connection.next_update().unwrap()
+		};
+		match play(GameAction::StartRandomGame).state.scene {
+			GameScene::AwaitingGuess { round, .. } => assert_eq!(round.word, "___"),
+			scene => panic!("unexpected scene {}", scene.name()),
+		}
+		play(GameAction::InputGuess(Some('c')));
+		play(GameAction::InputGuess(Some('a')));
+		let view = play(GameAction::InputGuess(Some('t')));
+
+		match view.state.scene {
+			GameScene::RoundEnd { round, won, .. } => {
+				assert!(won);
+				assert_eq!(round.word, "cat");
+			}
+			scene => panic!("unexpected scene {}", scene.name()),
+		}
+		assert_eq!(view.players.len(), 1);
+		assert_eq!(view.players[0].name, "Alice");
+		assert!(view.players[0].score > 0.0);
+		assert_eq!(view.last_player.as_deref(), Some("Alice"));
+	}
+
+	#[test]
+	fn names_are_taken_once() {
+		let address = host_on_loopback(&["cat"]);
+		let _alice = Connection::join(&address, "Alice").unwrap();
+
+		assert!(matches!(
+			Connection::join(&address, "Alice"),
+			Err(NetError::Rejected(_))
+		));
+	}
+}
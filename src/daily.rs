@@ -0,0 +1,124 @@
+use crate::alphabet::Alphabet;
+use crate::errors::StorageError;
+use crate::game_state::{GameSettings, Guess, HintKind, Round, WordSource};
+use crate::scoring::Scoring;
+use crate::storage::{data_dir, read_json, write_json};
+use crate::word_list::{get_filtered_word_list, WordFilter, WordListEntry};
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// The name of the daily challenge mode, e.g. in the high score table
+pub const DAILY_MODE: &str = "daily";
+
+/// Which words of the built-in list can be the word of the day
+pub const DAILY_FILTER: WordFilter = WordFilter {
+	min_length: 5,
+	max_length: 16,
+	min_score: 60,
+	max_score: 100,
+	category: None,
+};
+
+/// Everybody plays the daily challenge of a day with the same rules
+pub fn daily_settings(date: NaiveDate) -> GameSettings {
+	GameSettings {
+		word_source: WordSource::Random,
+		max_wrong_guesses: 6,
+		solve_penalty: 2,
+		alphabet: Alphabet::default(),
+		seed: None,
+		max_hints: 0,
+		hint_kind: HintKind::Random,
+		hint_penalty: 0.0,
+		time_limit: None,
+		evil: false,
+		boards: 1,
+		scoring: Scoring::default(),
+		daily_date: Some(date),
+	}
+}
+
+/// The word of the day, the same on every machine with the same word list.
+/// `None` if no word of the list matches [DAILY_FILTER].
+pub fn daily_word(word_list: &[WordListEntry], date: NaiveDate) -> Option<WordListEntry> {
+	let seed = date.num_days_from_ce() as u64;
+	get_filtered_word_list(word_list, &DAILY_FILTER, &Alphabet::default(), seed).pop()
+}
+
+/// A spoiler-free summary of a round that can be shared with others: a
+/// square for every guess, green for correct letters and red for wrong ones.
+pub fn share_summary(date: NaiveDate, round: &Round, won: bool, max_wrong_guesses: u8) -> String {
+	let word_letters = round.word_letters();
+	let squares: String = round
+		.history
+		.iter()
+		.map(|guess| match guess {
+			Guess::Letter(letter) if word_letters.contains(letter) => "🟩",
+			Guess::Letter(_) => "🟥",
+			Guess::Solve(phrase) if round.is_solved_by(phrase) => "⭐",
+			Guess::Solve(_) => "❌",
+			Guess::Hint(_) => "💡",
+		})
+		.collect();
+
+	let result = if won {
+		format!("{}/{}", round.wrong_guesses(), max_wrong_guesses)
+	} else {
+		format!("X/{}", max_wrong_guesses)
+	};
+
+	format!("Hangman daily {} {}\n{}", date, result, squares)
+}
+
+/// How a daily challenge went
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyOutcome {
+	pub won: bool,
+	pub score: f64,
+	/// See [share_summary]
+	pub summary: String,
+}
+
+/// A daily challenge somebody played on this machine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyResult {
+	pub date: NaiveDate,
+	pub player: String,
+	/// `None` while the challenge is being played, or if it was abandoned
+	pub outcome: Option<DailyOutcome>,
+}
+
+/// All daily challenges that were played on this machine
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DailyResults {
+	pub results: Vec<DailyResult>,
+}
+
+impl DailyResults {
+	pub fn default_path() -> Result<PathBuf, StorageError> {
+		Ok(data_dir()?.join("daily.json"))
+	}
+
+	pub fn load(path: &Path) -> Result<DailyResults, StorageError> {
+		Ok(read_json(path)?.unwrap_or_default())
+	}
+
+	pub fn save(&self, path: &Path) -> Result<(), StorageError> {
+		write_json(path, self)
+	}
+
+	/// The challenge the player played (or started) on that day
+	pub fn get(&self, date: NaiveDate, player: &str) -> Option<&DailyResult> {
+		self.results
+			.iter()
+			.find(|result| result.date == date && result.player == player)
+	}
+
+	/// Adds a result, replacing the one of the same player and day
+	pub fn set(&mut self, result: DailyResult) {
+		self.results
+			.retain(|other| other.date != result.date || other.player != result.player);
+		self.results.push(result);
+	}
+}
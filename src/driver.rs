@@ -0,0 +1,48 @@
+use crate::errors::DriverError;
+use crate::game_state::{GameAction, GameState};
+
+/// Something that can show a game to players and collect their input.
+///
+/// The terminal game is one implementation, but anything that can turn a
+/// [GameState] into pixels, text or network packets can be one.
+pub trait Frontend {
+	type Error;
+
+	/// Shows the current scene of the game
+	fn render(&mut self, state: &GameState) -> Result<(), Self::Error>;
+
+	/// Figures out what should happen next in the current scene, e.g. by
+	/// waiting for a keypress. Never called once the game is over.
+	fn next_action(&mut self, state: &GameState) -> Result<GameAction, Self::Error>;
+}
+
+/// Pulls the next action from the frontend, applies it and renders the result.
+///
+/// Use this instead of [run] to stay in control between transitions, e.g. to
+/// pause a game.
+pub fn step<F: Frontend>(
+	frontend: &mut F,
+	state: GameState,
+) -> Result<GameState, DriverError<F::Error>> {
+	let action = frontend
+		.next_action(&state)
+		.map_err(DriverError::Frontend)?;
+	let state = state.apply(action)?;
+	frontend.render(&state).map_err(DriverError::Frontend)?;
+	Ok(state)
+}
+
+/// Renders the game and keeps stepping until it is over. Returns the finished
+/// game, e.g. to read the final score.
+pub fn run<F: Frontend>(
+	frontend: &mut F,
+	state: GameState,
+) -> Result<GameState, DriverError<F::Error>> {
+	frontend.render(&state).map_err(DriverError::Frontend)?;
+
+	let mut state = state;
+	while !state.is_over() {
+		state = step(frontend, state)?;
+	}
+	Ok(state)
+}
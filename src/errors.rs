@@ -1,64 +1,268 @@
-use clap::{Error as ClapError, ErrorKind as ClapErrorKind};
+use crate::game_state::GameScene;
 use std::error::Error;
 use std::fmt::{self, Display, Formatter};
+use std::io;
+use std::path::{Path, PathBuf};
 
+/// Occurs when a [crate::game_state::GameState] transition is called in a
+/// scene it can't be applied to, e.g. guessing a letter before the round has
+/// started. This always means the frontend driving the game has a bug.
 #[derive(Debug, Clone)]
-pub enum ValidationError {
-	/// Occurs in random mode, when the passed parameters did not match any
-	/// entries.
-	NoWordsFound,
+pub struct TransitionError {
+	/// The name of the transition that was attempted
+	pub action: &'static str,
+	/// The name of the scene the game was in, see [GameScene::name]
+	pub scene: &'static str,
+}
 
-	/// Occurs in random mode, when the passed parameters did not match any
-	/// entries other than the ones already played.
-	NoMoreWordsFound,
+impl TransitionError {
+	pub fn new(action: &'static str, scene: &GameScene) -> Self {
+		TransitionError {
+			action,
+			scene: scene.name(),
+		}
+	}
+}
 
-	/// Occurs in random mode, when a passed min length is higher than max length
-	MinLengthIsBiggerThanMaxLength { min_length: u8, max_length: u8 },
+impl Error for TransitionError {}
 
-	/// Occurs in random mode, when a passed min score is higher than max score
-	MinScoreIsBiggerThanMaxScore { min_score: u8, max_score: u8 },
+impl Display for TransitionError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"Invalid scene transition {} executed on scene {}",
+			self.action, self.scene
+		)
+	}
 }
 
-impl Error for ValidationError {}
+/// Everything that can stop [crate::driver::run] before the game is over
+#[derive(Debug)]
+pub enum DriverError<E> {
+	/// The frontend failed to render or to read input
+	Frontend(E),
 
-impl From<ValidationError> for ClapError {
-	fn from(e: ValidationError) -> Self {
-		match e {
-			ValidationError::NoWordsFound => ClapError::with_description(
-				"Could not find any words using the given parameters".to_string(),
-				ClapErrorKind::ValueValidation,
-			),
-			ValidationError::NoMoreWordsFound => ClapError::with_description(
-				"Could not find any more words using the given parameters".to_string(),
-				ClapErrorKind::ValueValidation,
+	/// The frontend asked for a transition that doesn't fit the current scene
+	Transition(TransitionError),
+}
+
+impl<E: Error> Error for DriverError<E> {}
+
+impl<E: Display> Display for DriverError<E> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			DriverError::Frontend(error) => error.fmt(f),
+			DriverError::Transition(error) => error.fmt(f),
+		}
+	}
+}
+
+impl<E> From<TransitionError> for DriverError<E> {
+	fn from(e: TransitionError) -> Self {
+		DriverError::Transition(e)
+	}
+}
+
+/// Occurs when a word list can't be read, or has lines that don't follow the
+/// `phrase::score` format. `source` is the file (or other source) of the list,
+/// `line` starts at 1.
+#[derive(Debug)]
+pub enum WordListError {
+	Unreadable {
+		source: String,
+		error: io::Error,
+	},
+
+	/// The line is not of the form `phrase::score`, optionally followed by
+	/// `::category::clue::difficulty`
+	BadLine {
+		source: String,
+		line: usize,
+		content: String,
+	},
+
+	/// The score is not a number from 0 to 100
+	BadScore {
+		source: String,
+		line: usize,
+		score: String,
+	},
+
+	EmptyPhrase {
+		source: String,
+		line: usize,
+	},
+
+	/// The difficulty is not one of easy, medium or hard
+	BadDifficulty {
+		source: String,
+		line: usize,
+		difficulty: String,
+	},
+}
+
+impl Error for WordListError {}
+
+impl Display for WordListError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			WordListError::Unreadable { source, error } => {
+				write!(f, "Could not read word list {}: {}", source, error)
+			}
+			WordListError::BadLine {
+				source,
+				line,
+				content,
+			} => write!(
+				f,
+				"{}:{}: expected a line of the form \"phrase::score\", optionally followed by \"::category::clue::difficulty\", found \"{}\"",
+				source, line, content
 			),
-			ValidationError::MinLengthIsBiggerThanMaxLength {
-				min_length,
-				max_length,
-			} => ClapError::with_description(
-				format!(
-					"Min word length ({}) must be smaller than max length ({})",
-					min_length, max_length
-				),
-				ClapErrorKind::ValueValidation,
+			WordListError::BadScore {
+				source,
+				line,
+				score,
+			} => write!(
+				f,
+				"{}:{}: the score must be a number from 0 to 100, found \"{}\"",
+				source, line, score
 			),
-			ValidationError::MinScoreIsBiggerThanMaxScore {
-				min_score,
-				max_score,
-			} => ClapError::with_description(
-				format!(
-					"Min word score ({}) must be smaller than max score ({})",
-					min_score, max_score
-				),
-				ClapErrorKind::ValueValidation,
+			WordListError::EmptyPhrase { source, line } => {
+				write!(f, "{}:{}: the phrase is empty", source, line)
+			}
+			WordListError::BadDifficulty {
+				source,
+				line,
+				difficulty,
+			} => write!(
+				f,
+				"{}:{}: the difficulty must be easy, medium or hard, found \"{}\"",
+				source, line, difficulty
 			),
 		}
 	}
 }
 
-impl Display for ValidationError {
+/// Occurs when a networked game can't go on, on the host or on a client
+#[derive(Debug)]
+pub enum NetError {
+	Io(io::Error),
+
+	/// A message could not be turned into JSON
+	Json(serde_json::Error),
+
+	/// A line that is not a message of the protocol
+	BadMessage {
+		line: String,
+		error: serde_json::Error,
+	},
+
+	/// The other side closed the connection
+	Disconnected,
+
+	/// A message that doesn't fit the conversation, e.g. an update before
+	/// being welcomed to the game
+	Unexpected(String),
+
+	/// Another player already joined under that name
+	NameTaken(String),
+
+	/// A client sent a message before joining
+	UnknownPlayer,
+
+	/// A client sent an action only the host may take, e.g. ending the game
+	/// for everybody
+	NotAllowed(String),
+
+	/// A client sent an action that doesn't fit the scene of its game,
+	/// usually because another player was quicker
+	Transition(TransitionError),
+
+	/// The host turned the client away, with the reason it gave
+	Rejected(String),
+}
+
+impl Error for NetError {}
+
+impl Display for NetError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			NetError::Io(error) => write!(f, "Network error: {}", error),
+			NetError::Json(error) => write!(f, "Could not encode a message: {}", error),
+			NetError::BadMessage { line, error } => {
+				write!(f, "Received a broken message \"{}\": {}", line, error)
+			}
+			NetError::Disconnected => write!(f, "The connection was closed"),
+			NetError::Unexpected(message) => write!(f, "Did not expect {}", message),
+			NetError::NameTaken(name) => {
+				write!(f, "Somebody called \"{}\" already joined", name)
+			}
+			NetError::UnknownPlayer => write!(f, "Join the game first"),
+			NetError::NotAllowed(action) => write!(f, "{} is up to the host", action),
+			NetError::Transition(error) => error.fmt(f),
+			NetError::Rejected(reason) => write!(f, "The host refused: {}", reason),
+		}
+	}
+}
+
+/// Occurs when a file the game keeps between sessions (like the high score
+/// table) can't be read or written
+#[derive(Debug)]
+pub enum StorageError {
+	/// There is no directory to keep files in, e.g. because `$HOME` is not set
+	NoDataDir,
+
+	/// Save slots may only contain letters, digits, `-` and `_`
+	BadSlotName(String),
+
+	Io {
+		path: PathBuf,
+		error: io::Error,
+	},
+
+	/// The file exists but is not what the game expects, or the data could not
+	/// be turned into JSON
+	Json {
+		path: PathBuf,
+		error: serde_json::Error,
+	},
+}
+
+impl StorageError {
+	pub fn io(path: &Path, error: io::Error) -> Self {
+		StorageError::Io {
+			path: path.to_path_buf(),
+			error,
+		}
+	}
+
+	pub fn json(path: &Path, error: serde_json::Error) -> Self {
+		StorageError::Json {
+			path: path.to_path_buf(),
+			error,
+		}
+	}
+}
+
+impl Error for StorageError {}
+
+impl Display for StorageError {
 	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-		let as_clap: ClapError = self.to_owned().into();
-		as_clap.fmt(f)
+		match self {
+			StorageError::NoDataDir => {
+				write!(f, "Could not find a directory to store game data in")
+			}
+			StorageError::BadSlotName(slot) => write!(
+				f,
+				"\"{}\" can't be used as a save slot, please only use letters, digits, - and _",
+				slot
+			),
+			StorageError::Io { path, error } => {
+				write!(f, "Could not access {}: {}", path.display(), error)
+			}
+			StorageError::Json { path, error } => {
+				write!(f, "{} is broken: {}", path.display(), error)
+			}
+		}
 	}
 }
@@ -1,327 +1,1423 @@
-use crate::{
-	validation::{GameMode, HangmanCliOptions, ManualGame, RandomGame},
-	word_list::get_filtered_word_list,
-};
-use std::collections::HashSet;
+use crate::alphabet::Alphabet;
+use crate::errors::TransitionError;
+use crate::scoring::{Bonuses, Scoring};
+use crate::word_list::{Difficulty, WordListEntry};
+use chrono::{DateTime, NaiveDate, Utc};
+use rand::seq::IteratorRandom;
+use serde::{Deserialize, Serialize};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{HashMap, HashSet};
 use std::fmt::{self, Debug, Formatter};
+use std::iter;
+use std::mem;
+use std::time::Duration;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum GuessFeedback {
 	LetsGo,
 	Correct(char),
 	Wrong(char),
+	/// A hint revealed this letter
+	Hint(char),
+	/// Somebody asked for a hint, but all of them were used up
+	NoHintsLeft,
 	AlreadyTried(char),
 	BadChar(Option<char>),
+	/// The phrase someone tried to solve with was wrong
+	WrongSolve(String),
+	/// Somebody tried to solve with a phrase that doesn't have any letters
+	BadSolve,
+	/// In rounds with several boards, the phrase solved one of them
+	BoardSolved(String),
+	/// In reverse games, the answer to the last guess doesn't fit the answers
+	/// that were given before, so the guess is asked again
+	Contradiction,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum EndFeedback {
 	NoWordsFound,
 	NoMoreWordsFound,
 	ManuallyEnded,
 }
 
-#[derive(Debug)]
+/// A single guess of a round
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Guess {
+	/// The letter key of a guessed letter, see [Alphabet::letter_key]
+	Letter(char),
+	/// An attempt to solve the whole phrase at once
+	Solve(String),
+	/// The letter key of a letter that was revealed by a hint
+	Hint(char),
+}
+
+/// What hidden letters of a word are replaced with, wherever the word is
+/// shown to players outside of the terminal game
+pub const MASK_CHAR: char = '_';
+
+/// The word of the current round and everything that was tried on it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Round {
+	pub word: String,
+	pub alphabet: Alphabet,
+	/// The letter keys (see [Alphabet::letter_key]) that were guessed
+	pub letters_guessed: HashSet<char>,
+	/// Wrong guesses on top of the wrongly guessed letters, e.g. for failed
+	/// attempts to solve the whole phrase
+	pub penalty_guesses: u8,
+	/// Every guess in the order it was made
+	pub history: Vec<Guess>,
+	/// When each guess of `history` was made. Games that were saved before
+	/// this was kept have fewer of them, for the guesses they began with.
+	#[serde(default)]
+	pub guessed_at: Vec<DateTime<Utc>>,
+	/// The letter keys that were revealed by hints, they're part of
+	/// `letters_guessed` as well
+	#[serde(default)]
+	pub hinted_letters: HashSet<char>,
+	/// What kind of thing the word is, if the word list said so
+	#[serde(default)]
+	pub category: Option<String>,
+	/// Something that helps guessing the word, if the word list has one
+	#[serde(default)]
+	pub clue: Option<String>,
+	#[serde(default)]
+	pub difficulty: Option<Difficulty>,
+	/// When the round began, for games with a [GameSettings::time_limit]
+	#[serde(default = "Utc::now")]
+	pub started_at: DateTime<Utc>,
+	/// Whether the round was lost because time ran out
+	#[serde(default)]
+	pub timed_out: bool,
+	/// The words an evil round could still be about, `word` is always one of
+	/// them. Empty in every other round.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub candidates: Vec<WordListEntry>,
+	/// The other boards of a round with several words at once (see
+	/// [GameSettings::boards]), the round itself is the first one. Every
+	/// board counts its own wrong guesses. Empty in rounds with a single word.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub boards: Vec<Round>,
+}
+
+impl Round {
+	pub fn new(word: String, alphabet: Alphabet) -> Round {
+		Round {
+			word,
+			alphabet,
+			letters_guessed: HashSet::new(),
+			penalty_guesses: 0,
+			history: Vec::new(),
+			guessed_at: Vec::new(),
+			hinted_letters: HashSet::new(),
+			category: None,
+			clue: None,
+			difficulty: None,
+			started_at: Utc::now(),
+			timed_out: false,
+			candidates: Vec::new(),
+			boards: Vec::new(),
+		}
+	}
+
+	/// A round for a word of a word list, with its category and clue
+	pub fn from_entry(entry: WordListEntry, alphabet: Alphabet) -> Round {
+		Round {
+			category: entry.category,
+			clue: entry.clue,
+			difficulty: entry.difficulty,
+			..Round::new(entry.phrase, alphabet)
+		}
+	}
+
+	/// A round for [GameSettings::evil] games, that may switch to any of
+	/// `words` that looks just like the entry before anything is guessed and
+	/// has the same category. Clues and difficulties would give the word away,
+	/// so evil rounds don't have them.
+	pub fn evil(entry: WordListEntry, words: &[WordListEntry], alphabet: Alphabet) -> Round {
+		let shape = mask(&entry.phrase, &alphabet, &HashSet::new());
+		let candidates = words
+			.iter()
+			.filter(|other| {
+				other.category == entry.category
+					&& mask(&other.phrase, &alphabet, &HashSet::new()) == shape
+			})
+			.cloned()
+			.collect();
+
+		let mut round = Round::from_entry(entry.clone(), alphabet);
+		round.clue = None;
+		round.difficulty = None;
+		round.candidates = [vec![entry], candidates].concat();
+		round
+	}
+
+	/// Whether the phrase is the word of the round, ignoring case, spaces
+	/// and punctuation
+	pub fn is_solved_by(&self, phrase: &str) -> bool {
+		self.alphabet.letter_keys(phrase) == self.alphabet.letter_keys(&self.word)
+	}
+
+	/// The keys of all distinct letters of the word
+	pub fn word_letters(&self) -> HashSet<char> {
+		self.word
+			.chars()
+			.filter_map(|c| self.alphabet.letter_key(c))
+			.collect()
+	}
+
+	/// Whether a character of the word is still hidden
+	pub fn is_hidden(&self, c: char) -> bool {
+		match self.alphabet.letter_key(c) {
+			Some(key) => !self.letters_guessed.contains(&key),
+			None => false,
+		}
+	}
+
+	/// The word as players may see it, with [MASK_CHAR] for every letter that
+	/// is still hidden
+	pub fn masked_word(&self) -> String {
+		mask(&self.word, &self.alphabet, &self.letters_guessed)
+	}
+
+	/// The letters of the word that were not guessed yet
+	pub fn hidden_letters(&self) -> HashSet<char> {
+		self.word_letters()
+			.difference(&self.letters_guessed)
+			.copied()
+			.collect()
+	}
+
+	pub fn wrong_guesses(&self) -> usize {
+		let wrong_letters = self
+			.letters_guessed
+			.difference(&self.word_letters())
+			.count();
+		wrong_letters + usize::from(self.penalty_guesses)
+	}
+
+	/// How long ago the round began
+	pub fn elapsed(&self) -> Duration {
+		(Utc::now() - self.started_at)
+			.to_std()
+			.unwrap_or(Duration::ZERO)
+	}
+
+	/// Every board of the round, starting with the round itself
+	pub fn all_boards(&self) -> impl Iterator<Item = &Round> {
+		iter::once(self).chain(&self.boards)
+	}
+
+	fn for_each_board(&mut self, mut f: impl FnMut(&mut Round)) {
+		let mut boards = mem::take(&mut self.boards);
+		f(self);
+		boards.iter_mut().for_each(f);
+		self.boards = boards;
+	}
+
+	/// The letter keys that were guessed on any of the boards. Boards that
+	/// are finished don't get the guesses that come after, so the round's own
+	/// `letters_guessed` can miss some.
+	pub fn guessed_letters(&self) -> HashSet<char> {
+		self.all_boards()
+			.flat_map(|board| board.letters_guessed.iter().copied())
+			.collect()
+	}
+
+	/// Whether a letter key was guessed on any of the boards
+	pub fn has_guessed(&self, key: char) -> bool {
+		self.all_boards()
+			.any(|board| board.letters_guessed.contains(&key))
+	}
+
+	/// How many hints were taken on all boards together
+	pub fn hints_taken(&self) -> usize {
+		self.all_boards()
+			.map(|board| board.hinted_letters.len())
+			.sum()
+	}
+
+	/// Whether all letters were found, or the phrase was solved at once
+	pub fn is_solved(&self) -> bool {
+		self.hidden_letters().is_empty()
+			|| self.history.iter().any(|guess| match guess {
+				Guess::Solve(phrase) => self.is_solved_by(phrase),
+				_ => false,
+			})
+	}
+
+	/// Whether the guess found letters of the word, or solved it
+	pub fn is_hit(&self, guess: &Guess) -> bool {
+		match guess {
+			Guess::Letter(key) | Guess::Hint(key) => self.word_letters().contains(key),
+			Guess::Solve(phrase) => self.is_solved_by(phrase),
+		}
+	}
+
+	/// Adds a guess to the history, made just now
+	fn push_guess(&mut self, guess: Guess) {
+		self.history.push(guess);
+		self.guessed_at.push(Utc::now());
+	}
+
+	/// Whether the board was solved or lost, and takes no more guesses
+	pub fn is_finished(&self, max_wrong_guesses: u8) -> bool {
+		self.is_solved() || self.wrong_guesses() > max_wrong_guesses.into()
+	}
+
+	/// The first board that is still being played, hints reveal its letters
+	fn hint_board(&mut self, max_wrong_guesses: u8) -> &mut Round {
+		let next = self
+			.boards
+			.iter()
+			.position(|board| !board.is_finished(max_wrong_guesses));
+		match next {
+			Some(index) if self.is_finished(max_wrong_guesses) => &mut self.boards[index],
+			_ => self,
+		}
+	}
+
+	/// Splits the candidates of an evil round into families by the places
+	/// the guessed letter shows up in, and keeps the biggest one. Ties go to
+	/// the family that reveals less. With `keep_word`, the family of the
+	/// current word is kept instead, e.g. when a hint promised the letter.
+	fn dodge_guess(&mut self, guess: char, keep_word: bool) {
+		let places = |phrase: &str| -> Vec<usize> {
+			phrase
+				.chars()
+				.enumerate()
+				.filter(|(_, c)| self.alphabet.letter_key(*c) == Some(guess))
+				.map(|(place, _)| place)
+				.collect()
+		};
+
+		let mut families: HashMap<Vec<usize>, Vec<WordListEntry>> = HashMap::new();
+		for entry in mem::take(&mut self.candidates) {
+			families
+				.entry(places(&entry.phrase))
+				.or_default()
+				.push(entry);
+		}
+		let current = places(&self.word);
+		let kept = if keep_word {
+			families.remove(&current)
+		} else {
+			families
+				.into_iter()
+				.max_by_key(|(places, family)| {
+					(family.len(), Reverse(places.len()), Reverse(places.clone()))
+				})
+				.map(|(_, family)| family)
+		};
+
+		self.candidates = kept.unwrap_or_default();
+		if !self
+			.candidates
+			.iter()
+			.any(|entry| entry.phrase == self.word)
+		{
+			self.switch_word();
+		}
+	}
+
+	/// Makes an evil round about another phrase than the one somebody tried
+	/// to solve it with, as long as any other candidate is left
+	fn dodge_solve(&mut self, phrase: &str) {
+		let keys = self.alphabet.letter_keys(phrase);
+		let (solved, others): (Vec<_>, Vec<_>) = mem::take(&mut self.candidates)
+			.into_iter()
+			.partition(|entry| self.alphabet.letter_keys(&entry.phrase) == keys);
+
+		if others.is_empty() {
+			self.candidates = solved;
+		} else {
+			self.candidates = others;
+			if self.is_solved_by(phrase) {
+				self.switch_word();
+			}
+		}
+	}
+
+	/// Settles on the first candidate for now
+	fn switch_word(&mut self) {
+		if let Some(entry) = self.candidates.first() {
+			self.word = entry.phrase.clone();
+		}
+	}
+}
+
+/// The phrase with [MASK_CHAR] for every letter whose key is not `revealed`
+fn mask(phrase: &str, alphabet: &Alphabet, revealed: &HashSet<char>) -> String {
+	phrase
+		.chars()
+		.map(|c| match alphabet.letter_key(c) {
+			Some(key) if !revealed.contains(&key) => MASK_CHAR,
+			_ => c,
+		})
+		.collect()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum GameScene {
 	Init,
 	AwaitingGuess {
-		word: String,
-		letters_guessed: HashSet<char>,
+		round: Round,
 		feedback: GuessFeedback,
 	},
 	ValidGuess {
-		word: String,
-		letters_guessed: HashSet<char>,
+		round: Round,
+		/// The letter key of the guess, see [Alphabet::letter_key]
 		guess: char,
 	},
+	/// Somebody is trying to solve the whole word or phrase at once
+	ValidSolve {
+		round: Round,
+		phrase: String,
+	},
 	RoundEnd {
-		word: String,
+		round: Round,
 		won: bool,
 		round_score: f64,
-		letters_guessed: HashSet<char>,
 	},
 	GameEnd {
 		feedback: EndFeedback,
 	},
 }
 
-/// helper for better debugging
-#[derive(Debug)]
-pub struct HugeWordsVec<'a> {
-	length: &'a usize,
-	next_ten: Vec<&'static str>,
+impl GameScene {
+	/// A short name for the scene, without any of its data
+	pub fn name(&self) -> &'static str {
+		match self {
+			GameScene::Init => "Init",
+			GameScene::AwaitingGuess { .. } => "AwaitingGuess",
+			GameScene::ValidGuess { .. } => "ValidGuess",
+			GameScene::ValidSolve { .. } => "ValidSolve",
+			GameScene::RoundEnd { .. } => "RoundEnd",
+			GameScene::GameEnd { .. } => "GameEnd",
+		}
+	}
+}
+
+/// Everything that can move a game from one scene to the next, so a driver can
+/// pass input along without knowing the individual transitions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum GameAction {
+	StartManualGame(String),
+	StartRandomGame,
+	InputGuess(Option<char>),
+	MakeGuess,
+	InputSolve(String),
+	MakeSolve,
+	TakeHint,
+	TimeUp,
+	GiveUp,
+	NewRound,
+	EndGame(EndFeedback),
+	StartReverseGame(Vec<usize>),
+	AnswerGuess(Vec<usize>),
+	AnswerSolve(bool),
+}
+
+/// Where the word for each new round comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WordSource {
+	/// Somebody enters every word by hand, see [GameState::start_manual_game]
+	Manual,
+	/// Words are drawn from the `unplayed_words` the game was created with,
+	/// see [GameState::start_random_game]
+	Random,
+	/// Somebody thinks of a word without telling it, and answers the guesses
+	/// of the computer, see [GameState::start_reverse_game]
+	Reverse,
+}
+
+/// Which hidden letter a hint reveals
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HintKind {
+	/// Any of the hidden letters
+	#[default]
+	Random,
+	/// The hidden letter that appears most often in the word
+	Common,
+}
+
+crate::named_enum!(HintKind, "kind of hint", {
+	Random => "random",
+	Common => "common",
+});
+
+/// How a round went, see [GameState::records]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoundRecord {
+	/// The word of the round, or the words of all its boards
+	pub words: Vec<String>,
+	/// Every guess in the order it was made
+	pub guesses: Vec<GuessRecord>,
+	pub hints: usize,
+	pub won: bool,
+	/// Whether the round was lost because time ran out
+	pub timed_out: bool,
+	pub score: f64,
+	/// Who guessed the word, in versus games
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub guesser: Option<String>,
+	pub started_at: DateTime<Utc>,
+	pub ended_at: DateTime<Utc>,
 }
 
+/// A single guess of a [RoundRecord]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GuessRecord {
+	pub guess: Guess,
+	/// When the guess was made, if that's known (see [Round::guessed_at])
+	pub at: Option<DateTime<Utc>>,
+	/// Whether it found letters of the word or solved it, of any board in
+	/// rounds with several of them
+	pub hit: bool,
+}
+
+impl RoundRecord {
+	fn new(round: &Round, won: bool, score: f64, guesser: Option<String>) -> RoundRecord {
+		// every guess goes to all boards that are still being played, so the
+		// board that was played the longest has all of them
+		let longest = round
+			.all_boards()
+			.max_by_key(|board| board.history.len())
+			.unwrap_or(round);
+		let untimed = longest
+			.history
+			.len()
+			.saturating_sub(longest.guessed_at.len());
+		let guesses = longest
+			.history
+			.iter()
+			.enumerate()
+			.map(|(index, guess)| GuessRecord {
+				guess: guess.clone(),
+				at: index
+					.checked_sub(untimed)
+					.map(|index| longest.guessed_at[index]),
+				hit: round.all_boards().any(|board| board.is_hit(guess)),
+			})
+			.collect();
+
+		RoundRecord {
+			words: round.all_boards().map(|board| board.word.clone()).collect(),
+			guesses,
+			hints: round.hints_taken(),
+			won,
+			timed_out: round.timed_out,
+			score,
+			guesser,
+			started_at: round.started_at,
+			ended_at: Utc::now(),
+		}
+	}
+
+	/// How many guesses found letters or solved the round
+	pub fn hits(&self) -> usize {
+		self.guesses.iter().filter(|guess| guess.hit).count()
+	}
+
+	pub fn misses(&self) -> usize {
+		self.guesses.len() - self.hits()
+	}
+
+	/// How long the round took
+	pub fn duration(&self) -> Duration {
+		(self.ended_at - self.started_at)
+			.to_std()
+			.unwrap_or(Duration::ZERO)
+	}
+}
+
+/// The rules a game is played with
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameSettings {
+	pub word_source: WordSource,
+	pub max_wrong_guesses: u8,
+	pub alphabet: Alphabet,
+	/// How many wrong guesses a failed attempt to solve the phrase costs
+	pub solve_penalty: u8,
+	/// The seed the words were shuffled with, if they were
+	pub seed: Option<u64>,
+	/// How many hints can be taken per round
+	#[serde(default)]
+	pub max_hints: u8,
+	#[serde(default)]
+	pub hint_kind: HintKind,
+	/// How much score every hint takes off a won round
+	#[serde(default)]
+	pub hint_penalty: f64,
+	/// How many seconds each round may take, the round is lost once they're up
+	#[serde(default)]
+	pub time_limit: Option<u32>,
+	/// Whether random rounds keep switching their word to dodge guesses, see
+	/// [Round::evil]
+	#[serde(default)]
+	pub evil: bool,
+	/// How many words random rounds have at once, see [Round::boards]
+	#[serde(default = "single_board")]
+	pub boards: u8,
+	/// The rule rounds are scored with
+	#[serde(default)]
+	pub scoring: Scoring,
+	/// The day whose daily challenge this is, see [crate::daily]
+	#[serde(default)]
+	pub daily_date: Option<NaiveDate>,
+}
+
+/// A classic game of random words, with six wrong guesses per round and
+/// nothing else turned on
+impl Default for GameSettings {
+	fn default() -> Self {
+		GameSettings {
+			word_source: WordSource::Random,
+			max_wrong_guesses: 6,
+			alphabet: Alphabet::default(),
+			solve_penalty: 2,
+			seed: None,
+			max_hints: 0,
+			hint_kind: HintKind::default(),
+			hint_penalty: 0.0,
+			time_limit: None,
+			evil: false,
+			boards: single_board(),
+			scoring: Scoring::default(),
+			daily_date: None,
+		}
+	}
+}
+
+fn single_board() -> u8 {
+	1
+}
+
+/// How many boards a round can have at most
+pub const MAX_BOARDS: u8 = 8;
+
+/// Somebody taking part in a versus game
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Player {
+	pub name: String,
+	pub score: f64,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct GameState {
-	pub args: HangmanCliOptions,
-	pub unplayed_words: Vec<&'static str>,
+	pub settings: GameSettings,
+	pub unplayed_words: Vec<WordListEntry>,
+	/// The words of every round that was played, won or lost
 	pub played_words: Vec<String>,
+	/// How every round that was played went, the first one first
+	#[serde(default)]
+	pub records: Vec<RoundRecord>,
 	pub score: f64,
 	pub scene: GameScene,
 	pub rounds_played: u32,
+	/// The players of a versus game, who take turns entering words for each
+	/// other. Empty in games where everybody plays together.
+	#[serde(default)]
+	pub players: Vec<Player>,
 }
 impl Debug for GameState {
 	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-		let some_unplayed_words = &mut self.unplayed_words.to_owned();
-		some_unplayed_words.reverse();
-		some_unplayed_words.truncate(10);
-		let unplayed_words_debug = &HugeWordsVec {
-			length: &self.unplayed_words.len(),
-			next_ten: some_unplayed_words.to_vec(),
-		};
+		// the word list can be huge, so only show what's coming up next
+		let next_ten: Vec<&str> = self
+			.unplayed_words
+			.iter()
+			.rev()
+			.take(10)
+			.map(|entry| entry.phrase.as_str())
+			.collect();
 
 		f.debug_struct("GameState")
 			.field("scene", &self.scene)
-			.field("args", &self.args)
+			.field("settings", &self.settings)
 			.field("score", &self.score)
 			.field("rounds_played", &self.rounds_played)
 			.field("played_words", &self.played_words)
-			.field("unplayed_words", &unplayed_words_debug)
+			.field("records", &self.records)
+			.field("players", &self.players)
+			.field(
+				"unplayed_words",
+				&format_args!("{} words, next: {:?}", self.unplayed_words.len(), next_ten),
+			)
 			.finish()
 	}
 }
 
-static GAME_WON_BASE_SCORE: f64 = 10.0;
-static GAME_WON_LEFT_GUESS_MULTIPLIER: f64 = 1.75;
-
 impl GameState {
-	pub fn new(args: HangmanCliOptions) -> GameState {
-		match args.mode {
-			GameMode::Manual(ManualGame { .. }) => GameState {
-				args,
-				unplayed_words: Vec::new(),
-				played_words: Vec::new(),
-				score: 0.0,
-				rounds_played: 0,
-				scene: GameScene::Init,
+	/// Creates a new game. `unplayed_words` are the words random rounds are
+	/// drawn from, starting with the last one. They are not needed for
+	/// [WordSource::Manual] games.
+	pub fn new(settings: GameSettings, unplayed_words: Vec<WordListEntry>) -> GameState {
+		let scene = match settings.word_source {
+			WordSource::Random if unplayed_words.is_empty() => GameScene::GameEnd {
+				feedback: EndFeedback::NoWordsFound,
 			},
-			GameMode::Random(RandomGame {
-				min_length,
-				max_length,
-				min_score,
-				max_score,
-				..
-			}) => {
-				let unplayed_words =
-					get_filtered_word_list(min_length, max_length, min_score, max_score);
-				if !unplayed_words.is_empty() {
-					GameState {
-						args,
-						unplayed_words,
-						played_words: Vec::new(),
-						score: 0.0,
-						rounds_played: 0,
-						scene: GameScene::Init,
-					}
-				} else {
-					GameState {
-						args,
-						unplayed_words,
-						played_words: Vec::new(),
-						score: 0.0,
-						rounds_played: 0,
-						scene: GameScene::GameEnd {
-							feedback: EndFeedback::NoWordsFound,
-						},
-					}
-				}
-			}
+			_ => GameScene::Init,
+		};
+
+		GameState {
+			settings,
+			unplayed_words,
+			played_words: Vec::new(),
+			records: Vec::new(),
+			score: 0.0,
+			rounds_played: 0,
+			scene,
+			players: Vec::new(),
+		}
+	}
+
+	/// Creates a versus game, where the players take turns entering a word for
+	/// the next one to guess. It needs at least two players and
+	/// [WordSource::Manual] settings.
+	pub fn new_versus(settings: GameSettings, player_names: Vec<String>) -> GameState {
+		GameState {
+			players: player_names
+				.into_iter()
+				.map(|name| Player { name, score: 0.0 })
+				.collect(),
+			..GameState::new(settings, Vec::new())
+		}
+	}
+
+	pub fn is_versus(&self) -> bool {
+		!self.players.is_empty()
+	}
+
+	/// The index of the round that is being played, or is about to start
+	fn round_index(&self) -> usize {
+		match self.scene {
+			GameScene::Init | GameScene::GameEnd { .. } => self.rounds_played as usize,
+			_ => (self.rounds_played as usize).saturating_sub(1),
+		}
+	}
+
+	/// Who guesses the current (or next) round of a versus game
+	pub fn guesser(&self) -> Option<&Player> {
+		let count = self.players.len();
+		self.players.get(self.round_index() % count.max(1))
+	}
+
+	/// Who enters the word of the current (or next) round of a versus game,
+	/// always the player after the guesser
+	pub fn setter(&self) -> Option<&Player> {
+		let count = self.players.len();
+		self.players.get((self.round_index() + 1) % count.max(1))
+	}
+
+	/// The players of a versus game, the best first
+	pub fn standings(&self) -> Vec<&Player> {
+		let mut standings: Vec<&Player> = self.players.iter().collect();
+		standings.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal));
+		standings
+	}
+
+	/// Applies a single transition
+	pub fn apply(self, action: GameAction) -> Result<GameState, TransitionError> {
+		match action {
+			GameAction::StartManualGame(word) => self.start_manual_game(word),
+			GameAction::StartRandomGame => self.start_random_game(),
+			GameAction::InputGuess(guess) => self.input_guess(guess),
+			GameAction::MakeGuess => self.make_guess(),
+			GameAction::InputSolve(phrase) => self.input_solve(phrase),
+			GameAction::MakeSolve => self.make_solve(),
+			GameAction::TakeHint => self.take_hint(),
+			GameAction::TimeUp => self.time_up(),
+			GameAction::GiveUp => self.give_up(),
+			GameAction::NewRound => Ok(self.new_round()),
+			GameAction::EndGame(feedback) => Ok(self.end_game(feedback)),
+			GameAction::StartReverseGame(lengths) => self.start_reverse_game(lengths),
+			GameAction::AnswerGuess(places) => self.answer_guess(places),
+			GameAction::AnswerSolve(correct) => self.answer_solve(correct),
+		}
+	}
+
+	pub fn is_reverse(&self) -> bool {
+		self.settings.word_source == WordSource::Reverse
+	}
+
+	pub fn is_over(&self) -> bool {
+		matches!(self.scene, GameScene::GameEnd { .. })
+	}
+
+	/// How much time is left for the round that is being played, `None` if
+	/// there is no time limit or no round is being played
+	pub fn time_left(&self) -> Option<Duration> {
+		match &self.scene {
+			GameScene::AwaitingGuess { round, .. }
+			| GameScene::ValidGuess { round, .. }
+			| GameScene::ValidSolve { round, .. } => self.round_time_left(round),
+			_ => None,
 		}
 	}
 
-	pub fn start_manual_game(self, word: String) -> GameState {
+	/// Starts the clock of the round that is being played again after a
+	/// pause, e.g. while the game was saved, as if the time in between never
+	/// passed
+	pub fn resume_after(&mut self, pause: Duration) {
+		if let GameScene::AwaitingGuess { round, .. }
+		| GameScene::ValidGuess { round, .. }
+		| GameScene::ValidSolve { round, .. } = &mut self.scene
+		{
+			let pause =
+				chrono::Duration::from_std(pause).unwrap_or_else(|_| chrono::Duration::zero());
+			round.for_each_board(|board| board.started_at += pause);
+		}
+	}
+
+	fn round_time_left(&self, round: &Round) -> Option<Duration> {
+		self.settings
+			.time_limit
+			.map(|limit| Duration::from_secs(limit.into()).saturating_sub(round.elapsed()))
+	}
+
+	fn is_time_up(&self, round: &Round) -> bool {
+		self.round_time_left(round) == Some(Duration::ZERO)
+	}
+
+	pub fn start_manual_game(self, word: String) -> Result<GameState, TransitionError> {
 		match self.scene {
-			GameScene::Init => GameState {
+			GameScene::Init => Ok(GameState {
 				scene: GameScene::AwaitingGuess {
-					word,
-					letters_guessed: HashSet::new(),
+					round: Round::new(word, self.settings.alphabet),
 					feedback: GuessFeedback::LetsGo,
 				},
 				rounds_played: self.rounds_played + 1,
 				..self
-			},
-			scene => panic!(
-				"Invalid scene transition start_manual_game executed on scene {:#?}",
-				scene
-			),
+			}),
+			scene => Err(TransitionError::new("start_manual_game", &scene)),
 		}
 	}
 
-	pub fn start_random_game(self) -> GameState {
+	pub fn start_random_game(self) -> Result<GameState, TransitionError> {
 		match self.scene {
 			GameScene::Init => {
 				let mut unplayed_words = self.unplayed_words;
 				let first_word = unplayed_words.pop();
+				let alphabet = self.settings.alphabet;
 				match first_word {
-					Some(word) => GameState {
+					Some(entry) => Ok(GameState {
 						scene: GameScene::AwaitingGuess {
-							word: word.to_string(),
-							letters_guessed: HashSet::new(),
+							round: if self.settings.evil {
+								Round::evil(entry, &unplayed_words, alphabet)
+							} else {
+								let mut round = Round::from_entry(entry, alphabet);
+								for _ in 1..self.settings.boards {
+									if let Some(entry) = unplayed_words.pop() {
+										round.boards.push(Round::from_entry(entry, alphabet));
+									}
+								}
+								round
+							},
 							feedback: GuessFeedback::LetsGo,
 						},
 						rounds_played: self.rounds_played + 1,
 						unplayed_words,
 						..self
-					},
-					None => GameState {
+					}),
+					None => Ok(GameState {
 						scene: GameScene::GameEnd {
 							feedback: EndFeedback::NoMoreWordsFound,
 						},
 						unplayed_words,
 						..self
-					},
+					}),
 				}
 			}
-			scene => panic!(
-				"Invalid scene transition start_random_game executed on scene {:#?}",
-				scene
-			),
+			scene => Err(TransitionError::new("start_random_game", &scene)),
 		}
 	}
 
-	pub fn input_guess(self, guess_input: Option<char>) -> GameState {
+	/// Starts a round of a reverse game, for a phrase with words of the given
+	/// lengths. Nobody types the phrase, its word is only [MASK_CHAR]s at
+	/// first, which are replaced with the letters that were found.
+	pub fn start_reverse_game(self, lengths: Vec<usize>) -> Result<GameState, TransitionError> {
 		match self.scene {
-			GameScene::AwaitingGuess {
-				word,
-				letters_guessed,
-				..
-			} => match guess_input {
-				Some(guess)
-					if letters_guessed
-						.get(&(guess.clone().to_ascii_lowercase()))
-						.is_some() =>
+			GameScene::Init if self.is_reverse() => {
+				let pattern = lengths
+					.iter()
+					.map(|length| MASK_CHAR.to_string().repeat(*length))
+					.collect::<Vec<_>>()
+					.join(" ");
+				Ok(GameState {
+					scene: GameScene::AwaitingGuess {
+						round: Round::new(pattern, self.settings.alphabet),
+						feedback: GuessFeedback::LetsGo,
+					},
+					rounds_played: self.rounds_played + 1,
+					..self
+				})
+			}
+			scene => Err(TransitionError::new("start_reverse_game", &scene)),
+		}
+	}
+
+	pub fn input_guess(mut self, guess_input: Option<char>) -> Result<GameState, TransitionError> {
+		match mem::replace(&mut self.scene, GameScene::Init) {
+			GameScene::AwaitingGuess { round, .. } if self.is_time_up(&round) => {
+				Ok(self.end_round_timed_out(round))
+			}
+			GameScene::AwaitingGuess { round, .. } => match guess_input
+				.and_then(|guess| round.alphabet.letter_key(guess).map(|key| (guess, key)))
+			{
+				Some((guess, key)) if round.has_guessed(key) => Ok(GameState {
+					scene: GameScene::AwaitingGuess {
+						round,
+						feedback: GuessFeedback::AlreadyTried(guess),
+					},
+					..self
+				}),
+				// letters outside of the alphabet can still be guessed when
+				// they're part of the word, e.g. in manual games
+				Some((_, key))
+					if round.alphabet.contains(key)
+						|| round
+							.all_boards()
+							.any(|board| board.word_letters().contains(&key)) =>
 				{
-					GameState {
-						scene: GameScene::AwaitingGuess {
-							word,
-							letters_guessed,
-							feedback: GuessFeedback::AlreadyTried(guess),
-						},
+					Ok(GameState {
+						scene: GameScene::ValidGuess { round, guess: key },
 						..self
-					}
+					})
 				}
-				Some(guess) if guess.is_ascii_alphabetic() => GameState {
-					scene: GameScene::ValidGuess {
-						word,
-						letters_guessed,
-						guess: guess.to_ascii_uppercase(),
-					},
-					..self
-				},
-				_ => GameState {
+				_ => Ok(GameState {
 					scene: GameScene::AwaitingGuess {
-						word,
-						letters_guessed,
+						round,
 						feedback: GuessFeedback::BadChar(guess_input),
 					},
 					..self
-				},
+				}),
 			},
-			scene => panic!(
-				"Invalid scene transition input_guess executed on scene {:#?}",
-				scene
-			),
+			scene => Err(TransitionError::new("input_guess", &scene)),
 		}
 	}
 
-	pub fn make_guess(self) -> GameState {
-		match self.scene {
-			GameScene::ValidGuess {
-				word,
-				mut letters_guessed,
-				guess,
-			} => {
-				let guess = guess.to_ascii_lowercase();
-				let word_letters: HashSet<char> = word
-					.chars()
-					.filter_map(|c| {
-						if c.is_ascii_alphabetic() {
-							Some(c.to_ascii_lowercase())
-						} else {
-							None
-						}
-					})
-					.collect();
+	pub fn make_guess(mut self) -> Result<GameState, TransitionError> {
+		match mem::replace(&mut self.scene, GameScene::Init) {
+			GameScene::ValidGuess { round, guess } if !round.boards.is_empty() => {
+				Ok(self.guess_on_boards(round, guess))
+			}
+			GameScene::ValidGuess { mut round, guess } if !self.is_reverse() => {
+				let hinted = round.hinted_letters.contains(&guess);
+				if !round.candidates.is_empty() {
+					round.dodge_guess(guess, hinted);
+				}
 
-				let guess_in_word = word_letters.get(&guess).is_some();
-				let wrong_guesses =
-					letters_guessed.len() - letters_guessed.intersection(&word_letters).count();
-
-				let max_wrong_guesses = match self.args.mode {
-					GameMode::Manual(ManualGame {
-						max_wrong_guesses, ..
-					}) => max_wrong_guesses,
-					GameMode::Random(RandomGame {
-						max_wrong_guesses, ..
-					}) => max_wrong_guesses,
-				};
+				let guess_in_word = round.word_letters().contains(&guess);
 
-				letters_guessed.insert(guess);
+				round.letters_guessed.insert(guess);
+				round.push_guess(if hinted {
+					Guess::Hint(guess)
+				} else {
+					Guess::Letter(guess)
+				});
 
 				if guess_in_word {
 					// This letter is correct!
-					if letters_guessed.intersection(&word_letters).count() == word_letters.len() {
+					if round.hidden_letters().is_empty() {
 						// Won!
-						let left_guesses =
-							i32::from(max_wrong_guesses) - i32::try_from(wrong_guesses).unwrap();
-						let left_guesses_multiplier: f64 =
-							GAME_WON_LEFT_GUESS_MULTIPLIER.powi(left_guesses);
-						let round_score: f64 =
-							(GAME_WON_BASE_SCORE * left_guesses_multiplier).round();
-						GameState {
-							scene: GameScene::RoundEnd {
-								won: true,
-								word,
-								round_score,
-								letters_guessed,
-							},
-							score: self.score + round_score,
-							..self
-						}
+						let round_score = self.won_round_score(&round);
+						Ok(self.end_round(round, true, round_score))
 					} else {
-						GameState {
+						Ok(GameState {
 							scene: GameScene::AwaitingGuess {
-								word,
-								letters_guessed,
-								feedback: GuessFeedback::Correct(guess.to_ascii_uppercase()),
+								round,
+								feedback: if hinted {
+									GuessFeedback::Hint(guess)
+								} else {
+									GuessFeedback::Correct(guess)
+								},
 							},
 							..self
-						}
+						})
 					}
 				} else {
 					// Wrong guess!
 					// Assumption: u8 can always fit into usize
-					if wrong_guesses + 1 > max_wrong_guesses.into() {
-						let mut played_words = self.played_words;
-						let word_copy = word.clone();
-						played_words.push(word);
+					if round.wrong_guesses() > self.settings.max_wrong_guesses.into() {
 						// Lost!
-						GameState {
-							scene: GameScene::RoundEnd {
-								won: false,
-								word: word_copy,
-								round_score: 0.0,
-								letters_guessed,
+						let round_score = self.lost_round_score(&round);
+						Ok(self.end_round(round, false, round_score))
+					} else {
+						Ok(GameState {
+							scene: GameScene::AwaitingGuess {
+								round,
+								feedback: GuessFeedback::Wrong(guess),
 							},
-							played_words,
 							..self
-						}
+						})
+					}
+				}
+			}
+			scene => Err(TransitionError::new("make_guess", &scene)),
+		}
+	}
+
+	pub fn input_solve(mut self, phrase: String) -> Result<GameState, TransitionError> {
+		match mem::replace(&mut self.scene, GameScene::Init) {
+			GameScene::AwaitingGuess { round, .. } if self.is_time_up(&round) => {
+				Ok(self.end_round_timed_out(round))
+			}
+			GameScene::AwaitingGuess { round, .. } => {
+				if round.alphabet.letter_keys(&phrase).is_empty() {
+					Ok(GameState {
+						scene: GameScene::AwaitingGuess {
+							round,
+							feedback: GuessFeedback::BadSolve,
+						},
+						..self
+					})
+				} else {
+					Ok(GameState {
+						scene: GameScene::ValidSolve { round, phrase },
+						..self
+					})
+				}
+			}
+			scene => Err(TransitionError::new("input_solve", &scene)),
+		}
+	}
+
+	/// Checks a whole phrase at once. Getting it right earns a bonus for every
+	/// letter that was still hidden, getting it wrong costs
+	/// [GameSettings::solve_penalty] wrong guesses.
+	pub fn make_solve(mut self) -> Result<GameState, TransitionError> {
+		match mem::replace(&mut self.scene, GameScene::Init) {
+			GameScene::ValidSolve { round, phrase } if !round.boards.is_empty() => {
+				Ok(self.solve_on_boards(round, phrase))
+			}
+			GameScene::ValidSolve { mut round, phrase } if !self.is_reverse() => {
+				round.push_guess(Guess::Solve(phrase.clone()));
+				if !round.candidates.is_empty() {
+					round.dodge_solve(&phrase);
+				}
+
+				if round.is_solved_by(&phrase) {
+					// Won!
+					let round_score = self.solved_round_score(&round);
+					Ok(self.end_round(round, true, round_score))
+				} else {
+					round.penalty_guesses = round
+						.penalty_guesses
+						.saturating_add(self.settings.solve_penalty);
+
+					if round.wrong_guesses() > self.settings.max_wrong_guesses.into() {
+						// Lost!
+						let round_score = self.lost_round_score(&round);
+						Ok(self.end_round(round, false, round_score))
 					} else {
-						GameState {
+						Ok(GameState {
 							scene: GameScene::AwaitingGuess {
-								word,
-								letters_guessed,
-								feedback: GuessFeedback::Wrong(guess.to_ascii_uppercase()),
+								round,
+								feedback: GuessFeedback::WrongSolve(phrase),
 							},
 							..self
-						}
+						})
 					}
 				}
 			}
-			scene => panic!(
-				"Invalid scene transition make_guess executed on scene {:#?}",
-				scene
-			),
+			scene => Err(TransitionError::new("make_solve", &scene)),
+		}
+	}
+
+	/// Makes a guess on every board of the round that is still being played.
+	/// It's correct if it's part of any of their words.
+	fn guess_on_boards(self, mut round: Round, guess: char) -> GameState {
+		let max_wrong_guesses = self.settings.max_wrong_guesses;
+		let hinted = round
+			.all_boards()
+			.any(|board| board.hinted_letters.contains(&guess));
+		let mut correct = false;
+		round.for_each_board(|board| {
+			if !board.is_finished(max_wrong_guesses) {
+				correct |= board.word_letters().contains(&guess);
+				board.letters_guessed.insert(guess);
+				board.push_guess(if board.hinted_letters.contains(&guess) {
+					Guess::Hint(guess)
+				} else {
+					Guess::Letter(guess)
+				});
+			}
+		});
+
+		let feedback = match (hinted, correct) {
+			(true, _) => GuessFeedback::Hint(guess),
+			(false, true) => GuessFeedback::Correct(guess),
+			(false, false) => GuessFeedback::Wrong(guess),
+		};
+		self.continue_on_boards(round, feedback)
+	}
+
+	/// Tries to solve the boards of the round that are still being played
+	/// with a phrase. It solves every board it fits, if it fits none of them,
+	/// all of them get [GameSettings::solve_penalty] wrong guesses.
+	fn solve_on_boards(self, mut round: Round, phrase: String) -> GameState {
+		let GameSettings {
+			max_wrong_guesses,
+			solve_penalty,
+			..
+		} = self.settings;
+		let solves_any = round
+			.all_boards()
+			.any(|board| !board.is_finished(max_wrong_guesses) && board.is_solved_by(&phrase));
+		round.for_each_board(|board| {
+			if !board.is_finished(max_wrong_guesses) {
+				board.push_guess(Guess::Solve(phrase.clone()));
+				if !solves_any {
+					board.penalty_guesses = board.penalty_guesses.saturating_add(solve_penalty);
+				}
+			}
+		});
+
+		let feedback = if solves_any {
+			GuessFeedback::BoardSolved(phrase)
+		} else {
+			GuessFeedback::WrongSolve(phrase)
+		};
+		self.continue_on_boards(round, feedback)
+	}
+
+	/// Waits for the next guess while any board is still being played, or
+	/// ends the round. It's won once every board is solved.
+	fn continue_on_boards(self, round: Round, feedback: GuessFeedback) -> GameState {
+		let max_wrong_guesses = self.settings.max_wrong_guesses;
+		if round
+			.all_boards()
+			.any(|board| !board.is_finished(max_wrong_guesses))
+		{
+			return GameState {
+				scene: GameScene::AwaitingGuess { round, feedback },
+				..self
+			};
+		}
+
+		let won = round.all_boards().all(Round::is_solved);
+		let round_score = self.boards_round_score(&round);
+		self.end_round(round, won, round_score)
+	}
+
+	/// Every solved board scores like a round of its own, including the bonus
+	/// for solving it at once, and the others like lost rounds. Solving all of
+	/// them multiplies the sum with [Bonuses::all_boards_solved_multiplier].
+	fn boards_round_score(&self, round: &Round) -> f64 {
+		let solved: Vec<&Round> = round
+			.all_boards()
+			.filter(|board| board.is_solved())
+			.collect();
+		let score: f64 = solved
+			.iter()
+			.map(|board| self.solved_round_score(board))
+			.sum();
+		if solved.len() == round.boards.len() + 1 {
+			score * self.bonuses().all_boards_solved_multiplier
+		} else {
+			let lost: f64 = round
+				.all_boards()
+				.filter(|board| !board.is_solved())
+				.map(|board| self.lost_round_score(board))
+				.sum();
+			score + lost
+		}
+	}
+
+	/// Answers the guess of a reverse game with the places the letter is at,
+	/// counting the letters of the phrase from 0. No places means the letter
+	/// isn't part of it. Places that are out of range or were already taken by
+	/// another letter, or a phrase that was said to be wrong before, are a
+	/// [GuessFeedback::Contradiction].
+	pub fn answer_guess(mut self, places: Vec<usize>) -> Result<GameState, TransitionError> {
+		match mem::replace(&mut self.scene, GameScene::Init) {
+			GameScene::ValidGuess { mut round, guess } if self.is_reverse() => {
+				let mut letters: Vec<char> = round.word.chars().filter(|c| *c != ' ').collect();
+				let mut fits = true;
+				for place in &places {
+					match letters.get_mut(*place) {
+						Some(letter) if *letter == MASK_CHAR || *letter == guess => *letter = guess,
+						_ => fits = false,
+					}
+				}
+				let mut letters = letters.into_iter();
+				let word: String = round
+					.word
+					.chars()
+					.map(|c| match c {
+						' ' => ' ',
+						_ => letters.next().unwrap_or(c),
+					})
+					.collect();
+
+				let is_complete = !word.contains(MASK_CHAR);
+				let was_rejected = round.history.iter().any(|earlier| match earlier {
+					Guess::Solve(phrase) => {
+						round.alphabet.letter_keys(phrase) == round.alphabet.letter_keys(&word)
+					}
+					_ => false,
+				});
+				if !fits || (is_complete && was_rejected) {
+					return Ok(GameState {
+						scene: GameScene::AwaitingGuess {
+							round,
+							feedback: GuessFeedback::Contradiction,
+						},
+						..self
+					});
+				}
+
+				round.word = word;
+				round.letters_guessed.insert(guess);
+				round.push_guess(Guess::Letter(guess));
+
+				if is_complete {
+					// the computer found every letter
+					let round_score = self.won_round_score(&round);
+					Ok(self.end_round(round, true, round_score))
+				} else if round.wrong_guesses() > self.settings.max_wrong_guesses.into() {
+					let round_score = self.lost_round_score(&round);
+					Ok(self.end_round(round, false, round_score))
+				} else {
+					let feedback = if places.is_empty() {
+						GuessFeedback::Wrong(guess)
+					} else {
+						GuessFeedback::Correct(guess)
+					};
+					Ok(GameState {
+						scene: GameScene::AwaitingGuess { round, feedback },
+						..self
+					})
+				}
+			}
+			scene => Err(TransitionError::new("answer_guess", &scene)),
+		}
+	}
+
+	/// Answers whether the phrase the computer tried to solve a reverse game
+	/// with is right. Saying yes to a phrase that doesn't fit the lengths of
+	/// the words or the letters that were found is a
+	/// [GuessFeedback::Contradiction].
+	pub fn answer_solve(mut self, correct: bool) -> Result<GameState, TransitionError> {
+		match mem::replace(&mut self.scene, GameScene::Init) {
+			GameScene::ValidSolve { mut round, phrase } if self.is_reverse() => {
+				let keys: Vec<char> = round.alphabet.letter_keys(&phrase).chars().collect();
+				let pattern: Vec<char> = round.word.chars().filter(|c| *c != ' ').collect();
+				let lengths: Vec<usize> = phrase
+					.split_whitespace()
+					.map(|word| round.alphabet.letter_keys(word).chars().count())
+					.filter(|length| *length > 0)
+					.collect();
+				let expected_lengths: Vec<usize> = round
+					.word
+					.split_whitespace()
+					.map(|word| word.chars().count())
+					.collect();
+				let fits = lengths == expected_lengths
+					&& keys.iter().zip(&pattern).all(|(key, known)| match *known {
+						MASK_CHAR => !round.letters_guessed.contains(key),
+						known => known == *key,
+					});
+				if correct && !fits {
+					return Ok(GameState {
+						scene: GameScene::AwaitingGuess {
+							round,
+							feedback: GuessFeedback::Contradiction,
+						},
+						..self
+					});
+				}
+
+				round.push_guess(Guess::Solve(phrase.clone()));
+				if correct {
+					round.word = phrase;
+					let round_score = self.solved_round_score(&round);
+					return Ok(self.end_round(round, true, round_score));
+				}
+
+				round.penalty_guesses = round
+					.penalty_guesses
+					.saturating_add(self.settings.solve_penalty);
+				if round.wrong_guesses() > self.settings.max_wrong_guesses.into() {
+					let round_score = self.lost_round_score(&round);
+					Ok(self.end_round(round, false, round_score))
+				} else {
+					Ok(GameState {
+						scene: GameScene::AwaitingGuess {
+							round,
+							feedback: GuessFeedback::WrongSolve(phrase),
+						},
+						..self
+					})
+				}
+			}
+			scene => Err(TransitionError::new("answer_solve", &scene)),
+		}
+	}
+
+	/// Picks a hidden letter according to [GameSettings::hint_kind] and makes
+	/// it the next guess. It costs [GameSettings::hint_penalty] once the
+	/// round is won, and no more than [GameSettings::max_hints] can be taken
+	/// per round.
+	pub fn take_hint(mut self) -> Result<GameState, TransitionError> {
+		match mem::replace(&mut self.scene, GameScene::Init) {
+			GameScene::AwaitingGuess { round, .. } if self.is_time_up(&round) => {
+				Ok(self.end_round_timed_out(round))
+			}
+			GameScene::AwaitingGuess { mut round, .. } => {
+				let hints_left = round.hints_taken() < self.settings.max_hints.into();
+				let board = round.hint_board(self.settings.max_wrong_guesses);
+				let letter = match self.settings.hint_kind {
+					HintKind::Random => board
+						.hidden_letters()
+						.into_iter()
+						.choose(&mut rand::thread_rng()),
+					HintKind::Common => {
+						let letter_keys = board.alphabet.letter_keys(&board.word);
+						board
+							.hidden_letters()
+							.into_iter()
+							// the earlier letter in the alphabet wins a tie
+							.max_by_key(|key| (letter_keys.matches(*key).count(), Reverse(*key)))
+					}
+				};
+
+				match letter {
+					Some(letter) if hints_left => {
+						board.hinted_letters.insert(letter);
+						Ok(GameState {
+							scene: GameScene::ValidGuess {
+								round,
+								guess: letter,
+							},
+							..self
+						})
+					}
+					_ => Ok(GameState {
+						scene: GameScene::AwaitingGuess {
+							round,
+							feedback: GuessFeedback::NoHintsLeft,
+						},
+						..self
+					}),
+				}
+			}
+			scene => Err(TransitionError::new("take_hint", &scene)),
+		}
+	}
+
+	/// Loses the round because its time ran out
+	pub fn time_up(mut self) -> Result<GameState, TransitionError> {
+		match mem::replace(&mut self.scene, GameScene::Init) {
+			GameScene::AwaitingGuess { round, .. } => Ok(self.end_round_timed_out(round)),
+			scene => Err(TransitionError::new("time_up", &scene)),
+		}
+	}
+
+	/// Loses the round on purpose, e.g. because a solver ran out of letters
+	/// to try
+	pub fn give_up(mut self) -> Result<GameState, TransitionError> {
+		match mem::replace(&mut self.scene, GameScene::Init) {
+			GameScene::AwaitingGuess { round, .. } => {
+				let round_score = self.unfinished_round_score(&round);
+				Ok(self.end_round(round, false, round_score))
+			}
+			scene => Err(TransitionError::new("give_up", &scene)),
+		}
+	}
+
+	fn end_round_timed_out(self, mut round: Round) -> GameState {
+		round.timed_out = true;
+		let round_score = self.unfinished_round_score(&round);
+		self.end_round(round, false, round_score)
+	}
+
+	/// The score of a round that ended before it was won or lost, e.g.
+	/// because its time ran out. Rounds with several boards still score the
+	/// boards that were solved, see [GameState::boards_round_score].
+	fn unfinished_round_score(&self, round: &Round) -> f64 {
+		if round.boards.is_empty() {
+			self.lost_round_score(round)
+		} else {
+			self.boards_round_score(round)
+		}
+	}
+
+	fn bonuses(&self) -> &Bonuses {
+		self.settings.scoring.rule().bonuses()
+	}
+
+	fn won_round_score(&self, round: &Round) -> f64 {
+		let score = self
+			.settings
+			.scoring
+			.rule()
+			.won_score(round, self.settings.max_wrong_guesses);
+		let speed_multiplier = match (self.settings.time_limit, self.round_time_left(round)) {
+			(Some(limit), Some(left)) if limit > 0 => {
+				1.0 + self.bonuses().speed_bonus * left.as_secs_f64() / f64::from(limit)
+			}
+			_ => 1.0,
+		};
+		let hint_penalty = round.hinted_letters.len() as f64 * self.settings.hint_penalty;
+		((score * speed_multiplier).round() - hint_penalty).max(0.0)
+	}
+
+	/// The score of a round that was won by solving it, with a bonus for every
+	/// letter that was still hidden
+	fn solved_round_score(&self, round: &Round) -> f64 {
+		let hidden_letters = round.hidden_letters().len() as f64;
+		self.won_round_score(round) + hidden_letters * self.bonuses().solve_hidden_letter_bonus
+	}
+
+	fn lost_round_score(&self, round: &Round) -> f64 {
+		self.settings
+			.scoring
+			.rule()
+			.lost_score(round, self.settings.max_wrong_guesses)
+			.round()
+	}
+
+	/// Ends the current round and adds `round_score`. In versus games it goes
+	/// to the guesser, and the setter gets [Bonuses::stumped_setter_score] as
+	/// well if the round was lost.
+	fn end_round(mut self, mut round: Round, won: bool, round_score: f64) -> GameState {
+		if !round.candidates.is_empty() {
+			// the word the round settled on shouldn't come up again
+			round.candidates.clear();
+			self.unplayed_words
+				.retain(|entry| entry.phrase != round.word);
+		}
+
+		let mut guesser_name = None;
+		if self.is_versus() {
+			let count = self.players.len();
+			// the round is still being played, but the scene was already
+			// taken out of the state
+			let guesser = (self.rounds_played as usize).saturating_sub(1) % count;
+			self.players[guesser].score += round_score;
+			if !won {
+				self.players[(guesser + 1) % count].score += self.bonuses().stumped_setter_score;
+			}
+			guesser_name = Some(self.players[guesser].name.clone());
+		}
+
+		let mut records = self.records;
+		records.push(RoundRecord::new(&round, won, round_score, guesser_name));
+		let mut played_words = self.played_words;
+		played_words.extend(round.all_boards().map(|board| board.word.clone()));
+		GameState {
+			scene: GameScene::RoundEnd {
+				won,
+				round,
+				round_score,
+			},
+			score: self.score + round_score,
+			played_words,
+			records,
+			..self
 		}
 	}
 
@@ -339,3 +1435,103 @@ impl GameState {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn settings(word_source: WordSource) -> GameSettings {
+		GameSettings {
+			word_source,
+			..GameSettings::default()
+		}
+	}
+
+	fn reverse_game(lengths: Vec<usize>) -> GameState {
+		GameState::new(settings(WordSource::Reverse), Vec::new())
+			.start_reverse_game(lengths)
+			.unwrap()
+	}
+
+	fn guess(state: GameState, letter: char) -> GameState {
+		state
+			.input_guess(Some(letter))
+			.and_then(GameState::make_guess)
+			.unwrap()
+	}
+
+	fn feedback(state: &GameState) -> Option<&GuessFeedback> {
+		match &state.scene {
+			GameScene::AwaitingGuess { feedback, .. } => Some(feedback),
+			_ => None,
+		}
+	}
+
+	#[test]
+	fn reverse_solves_need_the_same_words() {
+		let answered = |phrase: &str| {
+			reverse_game(vec![2, 2])
+				.input_solve(phrase.to_string())
+				.and_then(|state| state.answer_solve(true))
+				.unwrap()
+		};
+
+		assert!(matches!(
+			feedback(&answered("abc d")),
+			Some(GuessFeedback::Contradiction)
+		));
+		assert!(matches!(
+			answered("ab cd").scene,
+			GameScene::RoundEnd { won: true, .. }
+		));
+	}
+
+	#[test]
+	fn timed_out_boards_score_the_solved_ones() {
+		let words = vec![WordListEntry::new("dog", 50), WordListEntry::new("cat", 50)];
+		let mut state = GameState::new(
+			GameSettings {
+				boards: 2,
+				..GameSettings::default()
+			},
+			words,
+		)
+		.start_random_game()
+		.unwrap();
+		for letter in ['c', 'a', 't'] {
+			state = guess(state, letter);
+		}
+
+		match state.time_up().unwrap().scene {
+			GameScene::RoundEnd {
+				round,
+				won,
+				round_score,
+			} => {
+				assert!(!won);
+				assert!(round.timed_out);
+				assert!(round_score > 0.0);
+			}
+			scene => panic!("unexpected scene {}", scene.name()),
+		}
+	}
+
+	#[test]
+	fn resumed_rounds_keep_their_time() {
+		let settings = GameSettings {
+			time_limit: Some(60),
+			..GameSettings::default()
+		};
+		let mut state = GameState::new(settings, vec![WordListEntry::new("cat", 50)])
+			.start_random_game()
+			.unwrap();
+		if let GameScene::AwaitingGuess { round, .. } = &mut state.scene {
+			// saved 50 seconds into the round, and resumed 40 seconds later
+			round.started_at = Utc::now() - chrono::Duration::seconds(90);
+		}
+
+		state.resume_after(Duration::from_secs(40));
+		let left = state.time_left().unwrap();
+		assert!(left > Duration::from_secs(8) && left <= Duration::from_secs(10));
+	}
+}
@@ -0,0 +1,88 @@
+use crate::errors::StorageError;
+use crate::scoring::ScoringKind;
+use crate::storage::{data_dir, read_json, write_json};
+use crate::word_list::WordFilter;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+
+/// A finished game, as it is kept in the high score table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HighScoreEntry {
+	pub player: String,
+	/// The game mode, e.g. "random"
+	pub mode: String,
+	/// Which words could come up, `None` in modes where players enter the words
+	pub filter: Option<WordFilter>,
+	pub max_wrong_guesses: u8,
+	/// The rule the rounds were scored with
+	#[serde(default)]
+	pub scoring: ScoringKind,
+	pub score: f64,
+	pub rounds: u32,
+	pub date: NaiveDate,
+}
+
+impl HighScoreEntry {
+	/// Whether both entries were played with the same settings, only those
+	/// scores can be compared fairly
+	pub fn same_game(&self, other: &HighScoreEntry) -> bool {
+		self.mode == other.mode
+			&& self.filter == other.filter
+			&& self.max_wrong_guesses == other.max_wrong_guesses
+			&& self.scoring == other.scoring
+	}
+}
+
+/// All games that were ever recorded on this machine
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct HighScores {
+	pub entries: Vec<HighScoreEntry>,
+}
+
+impl HighScores {
+	pub fn default_path() -> Result<PathBuf, StorageError> {
+		Ok(data_dir()?.join("high-scores.json"))
+	}
+
+	/// Loads the table, it's empty if nothing was recorded yet
+	pub fn load(path: &Path) -> Result<HighScores, StorageError> {
+		Ok(read_json(path)?.unwrap_or_default())
+	}
+
+	pub fn save(&self, path: &Path) -> Result<(), StorageError> {
+		write_json(path, self)
+	}
+
+	/// Records a game, returns whether it beat every earlier game with the
+	/// same settings
+	pub fn add(&mut self, entry: HighScoreEntry) -> bool {
+		let is_high_score = entry.score > 0.0
+			&& self
+				.entries
+				.iter()
+				.filter(|other| other.same_game(&entry))
+				.all(|other| entry.score > other.score);
+
+		self.entries.push(entry);
+		is_high_score
+	}
+
+	/// The best entries first, only those `matches` returns true for
+	pub fn top<F>(&self, matches: F) -> Vec<&HighScoreEntry>
+	where
+		F: Fn(&HighScoreEntry) -> bool,
+	{
+		let mut top: Vec<&HighScoreEntry> =
+			self.entries.iter().filter(|entry| matches(entry)).collect();
+		top.sort_by(|a, b| {
+			b.score
+				.partial_cmp(&a.score)
+				.unwrap_or(Ordering::Equal)
+				// fewer rounds for the same score is more impressive
+				.then(a.rounds.cmp(&b.rounds))
+		});
+		top
+	}
+}
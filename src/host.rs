@@ -0,0 +1,165 @@
+use hangman::errors::NetError;
+use hangman::net::{self, ClientId, ClientMessage, HostGame, HostMessage};
+use std::collections::HashMap;
+use std::io::BufReader;
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+
+/// What happens on the connections to the players
+enum Event {
+	Connected(ClientId, TcpStream),
+	Message(ClientId, ClientMessage),
+	/// A line that is not a message of the protocol, the client can go on
+	BadMessage(ClientId, NetError),
+	/// The connection was closed, or broke with the error
+	Disconnected(ClientId, Option<NetError>),
+}
+
+/// Waits for players on the port and runs the game for them, until the host
+/// is stopped with Ctrl-C
+pub fn host_game(port: u16, game: HostGame) -> Result<(), NetError> {
+	let listener = TcpListener::bind(("0.0.0.0", port)).map_err(NetError::Io)?;
+	println!("Hosting a {} game on port {}", game.mode(), port);
+	println!(
+		"Players can join with `hangman join <address of this machine>:{}`",
+		port
+	);
+	println!("Press Ctrl-C to stop");
+
+	serve(listener, game);
+	Ok(())
+}
+
+/// Runs the game for the players that connect to the listener
+pub fn serve(listener: TcpListener, mut game: HostGame) {
+	let (events, received) = mpsc::channel();
+	thread::spawn(move || accept_players(listener, events));
+
+	let mut clients: HashMap<ClientId, TcpStream> = HashMap::new();
+	for event in received {
+		match event {
+			Event::Connected(id, stream) => {
+				clients.insert(id, stream);
+			}
+			Event::Message(id, ClientMessage::Join { name }) if game.player_name(id).is_none() => {
+				match game.join(id, name.clone()) {
+					Ok(()) => {
+						println!("{} joined", name);
+						let welcome = HostMessage::Welcome { mode: game.mode() };
+						reply(&mut clients, id, &welcome);
+						send_updates(&mut clients, &game, game.client_ids());
+					}
+					Err(error) => {
+						println!("Turned away a player: {}", error);
+						let rejected = HostMessage::Rejected {
+							reason: error.to_string(),
+						};
+						reply(&mut clients, id, &rejected);
+						disconnect(&mut clients, id);
+					}
+				}
+			}
+			Event::Message(id, ClientMessage::Join { .. }) => {
+				let rejected = HostMessage::Rejected {
+					reason: "You already joined".to_string(),
+				};
+				reply(&mut clients, id, &rejected);
+			}
+			Event::Message(id, ClientMessage::Action { action }) => match game.apply(id, action) {
+				Ok(ids) => send_updates(&mut clients, &game, ids),
+				Err(error) => {
+					let rejected = HostMessage::Rejected {
+						reason: error.to_string(),
+					};
+					reply(&mut clients, id, &rejected);
+					send_updates(&mut clients, &game, vec![id]);
+				}
+			},
+			Event::BadMessage(id, error) => {
+				let rejected = HostMessage::Rejected {
+					reason: error.to_string(),
+				};
+				reply(&mut clients, id, &rejected);
+			}
+			Event::Message(id, ClientMessage::Leave) | Event::Disconnected(id, None) => {
+				leave(&mut clients, &mut game, id);
+			}
+			Event::Disconnected(id, Some(error)) => {
+				if let Some(name) = game.player_name(id) {
+					println!("Lost the connection to {}: {}", name, error);
+				}
+				leave(&mut clients, &mut game, id);
+			}
+		}
+	}
+}
+
+/// Accepts connections and reads from each of them on a thread of its own,
+/// so the game only has to wait for the events
+fn accept_players(listener: TcpListener, events: Sender<Event>) {
+	for (id, stream) in (0..).zip(listener.incoming()) {
+		let (stream, reader) = match stream.and_then(|stream| Ok((stream.try_clone()?, stream))) {
+			Ok(streams) => streams,
+			Err(error) => {
+				println!("A player could not connect: {}", error);
+				continue;
+			}
+		};
+		if events.send(Event::Connected(id, stream)).is_err() {
+			return;
+		}
+
+		let events = events.clone();
+		thread::spawn(move || {
+			let mut reader = BufReader::new(reader);
+			loop {
+				let event = match net::receive(&mut reader) {
+					Ok(Some(message)) => Event::Message(id, message),
+					Ok(None) => Event::Disconnected(id, None),
+					Err(error @ NetError::BadMessage { .. }) => Event::BadMessage(id, error),
+					Err(error) => Event::Disconnected(id, Some(error)),
+				};
+				let disconnected = matches!(event, Event::Disconnected(..));
+				if events.send(event).is_err() || disconnected {
+					return;
+				}
+			}
+		});
+	}
+}
+
+/// Sends a message to a client. If that fails, the connection broke, which
+/// its reading thread notices as well.
+fn reply(clients: &mut HashMap<ClientId, TcpStream>, id: ClientId, message: &HostMessage) {
+	if let Some(stream) = clients.get_mut(&id) {
+		let _ = net::send(stream, message);
+	}
+}
+
+fn send_updates(clients: &mut HashMap<ClientId, TcpStream>, game: &HostGame, ids: Vec<ClientId>) {
+	for id in ids {
+		if let Some(update) = game.update_for(id) {
+			reply(clients, id, &update);
+		}
+	}
+}
+
+fn disconnect(clients: &mut HashMap<ClientId, TcpStream>, id: ClientId) {
+	if let Some(stream) = clients.remove(&id) {
+		let _ = stream.shutdown(Shutdown::Both);
+	}
+}
+
+/// Takes a player out of the game, and tells the others
+fn leave(clients: &mut HashMap<ClientId, TcpStream>, game: &mut HostGame, id: ClientId) {
+	if !clients.contains_key(&id) {
+		return;
+	}
+	if let Some(name) = game.player_name(id) {
+		println!("{} left", name);
+	}
+	game.leave(id);
+	disconnect(clients, id);
+	send_updates(clients, game, game.client_ids());
+}
@@ -1,6 +1,11 @@
 use std::{
 	collections::HashSet,
-	io::{stdout, Write},
+	fs::File,
+	io::{self, stdout, IsTerminal, Write},
+	process,
+	sync::atomic::{AtomicBool, Ordering},
+	thread,
+	time::{Duration, Instant},
 };
 
 use crossterm::{
@@ -10,20 +15,186 @@ use crossterm::{
 	terminal::{self, ClearType},
 };
 
-use crate::{render::format_word, terminate};
+use crate::render::format_word;
+use hangman::alphabet::Alphabet;
 
-pub fn get_char(prompt: &'static str) -> crossterm::Result<Option<char>> {
+/// Set when Ctrl-C was pressed while no input was being read, the next input
+/// is interrupted instead.
+pub static QUIT_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// The error all inputs return when the player pressed Ctrl-C
+fn interrupted() -> crossterm::ErrorKind {
+	io::Error::new(io::ErrorKind::Interrupted, "Interrupted")
+}
+
+pub fn is_interrupted(error: &crossterm::ErrorKind) -> bool {
+	error.kind() == io::ErrorKind::Interrupted
+}
+
+/// The error inputs with a deadline return when it passed
+fn timed_out() -> crossterm::ErrorKind {
+	io::Error::new(io::ErrorKind::TimedOut, "Time is up")
+}
+
+pub fn is_timed_out(error: &crossterm::ErrorKind) -> bool {
+	error.kind() == io::ErrorKind::TimedOut
+}
+
+/// Set when the game was changed by somebody else than the player, e.g. by
+/// another player of a networked game. The input that is being read stops
+/// with an error (see [is_outdated]), so the new state can be shown.
+pub static UPDATE_PENDING: AtomicBool = AtomicBool::new(false);
+
+/// The error inputs return when [UPDATE_PENDING] was set while waiting
+fn outdated() -> crossterm::ErrorKind {
+	io::Error::new(io::ErrorKind::WouldBlock, "The game changed")
+}
+
+pub fn is_outdated(error: &crossterm::ErrorKind) -> bool {
+	error.kind() == io::ErrorKind::WouldBlock
+}
+
+/// The error inputs return when the terminal is gone, e.g. because its
+/// window was closed
+fn terminal_closed() -> crossterm::ErrorKind {
+	io::Error::new(io::ErrorKind::UnexpectedEof, "The terminal was closed")
+}
+
+pub fn is_terminal_closed(error: &crossterm::ErrorKind) -> bool {
+	error.kind() == io::ErrorKind::UnexpectedEof
+}
+
+/// Whether there still is a terminal to read events from. crossterm reads
+/// them from stdin, or from the controlling terminal if stdin isn't one, and
+/// waits forever once that was closed.
+pub fn has_terminal() -> bool {
+	io::stdin().is_terminal() || cfg!(not(unix)) || File::open("/dev/tty").is_ok()
+}
+
+/// Quits the game once the terminal it is played in was closed. The check in
+/// [poll_event] isn't enough on its own: a terminal that is closed while
+/// crossterm waits for an event keeps it busy forever.
+pub fn watch_terminal() {
+	thread::spawn(|| loop {
+		thread::sleep(Duration::from_secs(1));
+		if !has_terminal() {
+			process::exit(1);
+		}
+	});
+}
+
+/// How often the countdown, [UPDATE_PENDING] and the terminal are checked
+/// while waiting for input
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Waits for the next event in raw mode, or at most `timeout`. Stops with an
+/// error when the game changed in the meantime (see [UPDATE_PENDING]), when
+/// the game should quit (Ctrl-C is a key in raw mode, so that's a signal like
+/// SIGTERM or SIGHUP) or when the terminal was closed.
+fn poll_event(timeout: Duration) -> crossterm::Result<Option<Event>> {
+	let started = Instant::now();
+	loop {
+		if !has_terminal() {
+			return Err(terminal_closed());
+		}
+		if QUIT_REQUESTED.swap(false, Ordering::SeqCst) {
+			terminal::disable_raw_mode()?;
+			println!();
+			return Err(interrupted());
+		}
+		if UPDATE_PENDING.load(Ordering::SeqCst) {
+			terminal::disable_raw_mode()?;
+			println!();
+			return Err(outdated());
+		}
+
+		let time_left = timeout.saturating_sub(started.elapsed());
+		if event::poll(time_left.min(POLL_INTERVAL))? {
+			return event::read().map(Some);
+		}
+		if time_left.is_zero() {
+			return Ok(None);
+		}
+	}
+}
+
+/// Waits for the next event in raw mode, see [poll_event]
+fn next_event() -> crossterm::Result<Event> {
+	loop {
+		if let Some(event) = poll_event(POLL_INTERVAL)? {
+			return Ok(event);
+		}
+	}
+}
+
+/// Shows the prompt and waits for the next event. With a deadline, the time
+/// that's left is shown in front of the prompt and counts down, and the wait
+/// ends with a timeout error once the deadline passed.
+fn read_event(prompt: &str, deadline: Option<Instant>) -> crossterm::Result<Event> {
+	let deadline = match deadline {
+		Some(deadline) => deadline,
+		None => {
+			print!("{}", prompt);
+			stdout().flush().unwrap();
+			return next_event();
+		}
+	};
+
+	let mut shown_seconds = None;
+	loop {
+		let time_left = deadline.saturating_duration_since(Instant::now());
+		if time_left.is_zero() {
+			terminal::disable_raw_mode()?;
+			println!();
+			return Err(timed_out());
+		}
+
+		// rounding up, so the countdown ends on 1 rather than 0
+		let seconds = time_left.as_secs() + u64::from(time_left.subsec_nanos() > 0);
+		if shown_seconds != Some(seconds) {
+			execute!(
+				stdout(),
+				terminal::Clear(ClearType::CurrentLine),
+				cursor::MoveToColumn(0)
+			)?;
+			print!("[{:>3}s] {}", seconds, prompt);
+			stdout().flush().unwrap();
+			shown_seconds = Some(seconds);
+		}
+
+		if let Some(event) = poll_event(time_left.min(POLL_INTERVAL))? {
+			return Ok(event);
+		}
+	}
+}
+
+/// Checks whether Ctrl-C was pressed since the last input
+fn check_quit_requested() -> crossterm::Result<()> {
+	if QUIT_REQUESTED.swap(false, Ordering::SeqCst) {
+		Err(interrupted())
+	} else {
+		Ok(())
+	}
+}
+
+/// Reads a single key, `None` if it's not a character. Without a key before
+/// the deadline, a timeout error is returned (see [is_timed_out]).
+pub fn get_char(
+	prompt: &'static str,
+	deadline: Option<Instant>,
+) -> crossterm::Result<Option<char>> {
+	check_quit_requested()?;
 	loop {
 		terminal::enable_raw_mode()?;
-		print!("{}", prompt);
-		stdout().flush().unwrap();
 
-		let key_event = match event::read()? {
+		let key_event = match read_event(prompt, deadline)? {
 			Event::Key(KeyEvent {
 				code: KeyCode::Char('c'),
 				modifiers: KeyModifiers::CONTROL,
 			}) => {
-				terminate();
+				terminal::disable_raw_mode()?;
+				println!();
+				return Err(interrupted());
 			}
 			Event::Key(KeyEvent {
 				code: KeyCode::Char(c),
@@ -47,18 +218,46 @@ pub fn get_char(prompt: &'static str) -> crossterm::Result<Option<char>> {
 	}
 }
 
+/// Waits at most `timeout` for a key, `None` if none was pressed
+pub fn poll_key(timeout: Duration) -> crossterm::Result<Option<KeyCode>> {
+	check_quit_requested()?;
+	terminal::enable_raw_mode()?;
+	let started = Instant::now();
+	let key = loop {
+		let time_left = timeout.saturating_sub(started.elapsed());
+		match poll_event(time_left)? {
+			Some(Event::Key(KeyEvent {
+				code: KeyCode::Char('c'),
+				modifiers: KeyModifiers::CONTROL,
+			})) => {
+				terminal::disable_raw_mode()?;
+				println!();
+				return Err(interrupted());
+			}
+			Some(Event::Key(KeyEvent { code, .. })) => break Some(code),
+			Some(_) if !time_left.is_zero() => (),
+			_ => break None,
+		}
+	};
+	terminal::disable_raw_mode()?;
+	Ok(key)
+}
+
 pub fn confirm_yn(prompt: &'static str) -> crossterm::Result<bool> {
+	check_quit_requested()?;
 	loop {
 		terminal::enable_raw_mode()?;
 		print!("{}", prompt);
 		stdout().flush().unwrap();
 
-		let key_event = match event::read()? {
+		let key_event = match next_event()? {
 			Event::Key(KeyEvent {
 				code: KeyCode::Char('c'),
 				modifiers: KeyModifiers::CONTROL,
 			}) => {
-				terminate();
+				terminal::disable_raw_mode()?;
+				println!();
+				return Err(interrupted());
 			}
 			Event::Key(KeyEvent {
 				code: KeyCode::Enter,
@@ -89,15 +288,18 @@ pub fn confirm_yn(prompt: &'static str) -> crossterm::Result<bool> {
 }
 
 pub fn confirm_enter() -> crossterm::Result<()> {
+	check_quit_requested()?;
 	loop {
 		terminal::enable_raw_mode()?;
 
-		match event::read()? {
+		match next_event()? {
 			Event::Key(KeyEvent {
 				code: KeyCode::Char('c'),
 				modifiers: KeyModifiers::CONTROL,
 			}) => {
-				terminate();
+				terminal::disable_raw_mode()?;
+				println!();
+				return Err(interrupted());
 			}
 			Event::Key(KeyEvent {
 				code: KeyCode::Enter,
@@ -112,25 +314,38 @@ pub fn confirm_enter() -> crossterm::Result<()> {
 	}
 }
 
-pub fn get_word(prompt: &'static str) -> crossterm::Result<String> {
+/// Lets the user type a whole word or phrase. With `hidden`, letters are shown
+/// as underscores so nobody can peek over their shoulder. Like [get_char], it
+/// can have a deadline.
+pub fn get_word(
+	prompt: &'static str,
+	hidden: bool,
+	deadline: Option<Instant>,
+) -> crossterm::Result<String> {
+	check_quit_requested()?;
 	let mut word: String = String::new();
 	let empty_map: HashSet<char> = HashSet::new();
 
 	loop {
 		terminal::enable_raw_mode()?;
-		print!(
-			"{}{}",
-			prompt,
-			format_word(word.as_str(), &empty_map, false)
-		);
-		stdout().flush().unwrap();
+		let line = if hidden {
+			format!(
+				"{}{}",
+				prompt,
+				format_word(word.as_str(), &empty_map, &Alphabet::default(), false)
+			)
+		} else {
+			format!("{}{}", prompt, word)
+		};
 
-		match event::read()? {
+		match read_event(&line, deadline)? {
 			Event::Key(KeyEvent {
 				code: KeyCode::Char('c'),
 				modifiers: KeyModifiers::CONTROL,
 			}) => {
-				terminate();
+				terminal::disable_raw_mode()?;
+				println!();
+				return Err(interrupted());
 			}
 			Event::Key(KeyEvent {
 				code: KeyCode::Char(c),
@@ -0,0 +1,34 @@
+//! The hangman game engine.
+//!
+//! [game_state::GameState] holds everything about a running session and moves
+//! from scene to scene through its transition methods. It never reads input or
+//! writes to the terminal, so any frontend can drive it, usually through the
+//! loop in [driver]. The bundled word list lives in [word_list], which letters
+//! can be guessed is up to the [alphabet::Alphabet] of a game. Everything that
+//! is kept between sessions, like the [high_scores], [saves] or [daily]
+//! results, goes through [storage]. Games over the network are run by a
+//! [net::HostGame], which sends every player their view of it. The
+//! [solver] plays rounds on its own, from what a player can see of them. How
+//! rounds are scored is up to the [scoring::ScoringRule] of a game. A
+//! [recording::Recorder] writes everything a frontend shows and does to a
+//! file, to play it back later.
+
+pub mod alphabet;
+pub mod daily;
+pub mod driver;
+pub mod errors;
+pub mod game_state;
+pub mod high_scores;
+mod macros;
+pub mod net;
+pub mod recording;
+pub mod saves;
+pub mod scoring;
+pub mod solver;
+pub mod storage;
+pub mod word_list;
+
+/// Runs the example of the README with the other doctests
+#[cfg(doctest)]
+#[doc = include_str!("../README.md")]
+struct ReadmeDoctests;
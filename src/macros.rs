@@ -0,0 +1,52 @@
+/// Gives an enum of plain variants the name each of them goes by on the
+/// command line: `ALL` of them, `name()`, a `Display` with that name and a
+/// `FromStr` that finds it again, ignoring case. `what` is what the enum is
+/// called in the error for names that don't exist.
+///
+/// ```
+/// #[derive(Debug, Clone, Copy)]
+/// enum Mode {
+///     Coop,
+///     Race,
+/// }
+///
+/// hangman::named_enum!(Mode, "mode", {
+///     Coop => "coop",
+///     Race => "race",
+/// });
+///
+/// assert_eq!("RACE".parse::<Mode>().unwrap().name(), "race");
+/// assert_eq!("solo".parse::<Mode>().unwrap_err(), "Unknown mode \"solo\"");
+/// ```
+#[macro_export]
+macro_rules! named_enum {
+	($name:ident, $what:literal, { $($variant:ident => $text:literal),+ $(,)? }) => {
+		impl $name {
+			pub const ALL: &'static [$name] = &[$($name::$variant),+];
+
+			pub fn name(&self) -> &'static str {
+				match self {
+					$($name::$variant => $text,)+
+				}
+			}
+		}
+
+		impl ::std::fmt::Display for $name {
+			fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+				f.write_str(self.name())
+			}
+		}
+
+		impl ::std::str::FromStr for $name {
+			type Err = String;
+
+			fn from_str(name: &str) -> Result<Self, Self::Err> {
+				$name::ALL
+					.iter()
+					.find(|value| value.name().eq_ignore_ascii_case(name))
+					.copied()
+					.ok_or_else(|| format!("Unknown {} \"{}\"", $what, name))
+			}
+		}
+	};
+}
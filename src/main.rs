@@ -1,34 +1,318 @@
 mod art;
-mod errors;
-mod game_state;
+mod autoplay;
+mod client;
+mod host;
 mod input;
+mod protocol;
 mod render;
+mod replay;
+mod reverse;
 mod validation;
-mod word_list;
 
 use crossterm::{
 	cursor,
 	terminal::{self, ClearType},
 };
-use game_state::GameState;
+use hangman::{
+	alphabet::Alphabet,
+	daily::{daily_settings, daily_word, DailyResult, DailyResults, DAILY_FILTER},
+	driver::{self, Frontend},
+	errors::{DriverError, StorageError},
+	game_state::{GameSettings, GameState, HintKind, WordSource},
+	high_scores::HighScores,
+	net::HostGame,
+	recording::{Recorder, Recording, RecordingFile},
+	saves::{SavedGame, Session},
+	scoring::{Scoring, ScoringConfig, ScoringKind},
+	solver::Solver,
+	word_list::{
+		builtin_word_list, get_filtered_word_list, random_seed, read_word_list, WordFilter,
+		WordListEntry,
+	},
+};
 
-use validation::{HangmanCliOptions, Validatable};
+use validation::{
+	DailyGame, GameMode, HangmanCliOptions, HostedGame, JoinGame, ManualGame, RandomGame,
+	ReplayGame, ResumeGame, ReverseGame, ShowScores, SolveGame, Validatable, ValidationError,
+	VersusGame,
+};
 
+use chrono::Local;
 use clap::{Clap, Error as ClapError};
 
-use std::{io::stdout, process::exit};
+use std::{
+	error::Error,
+	io::{self, stdout},
+	process::exit,
+	sync::atomic::Ordering,
+};
+
+use art::load_theme;
+use autoplay::{AutoplayFrontend, SolverStats};
+use client::{play, Connection};
+use host::host_game;
+use input::{has_terminal, is_interrupted, is_terminal_closed, watch_terminal, QUIT_REQUESTED};
+use protocol::{JsonlFrontend, Protocol};
+use render::{print_high_scores, TerminalFrontend};
+use replay::replay;
+use reverse::ReverseFrontend;
 
-use render::render_game;
+/// The rules of games where players enter the words
+fn manual_settings(manual: &ManualGame) -> GameSettings {
+	let ManualGame {
+		max_wrong_guesses,
+		solve_penalty,
+		alphabet,
+		fold_accents,
+		time_limit,
+	} = manual;
 
-fn hangman_game(args: HangmanCliOptions) {
-	let state = GameState::new(args);
-	// TODO: term Errors
-	render_game(state).unwrap();
+	GameSettings {
+		word_source: WordSource::Manual,
+		max_wrong_guesses: *max_wrong_guesses,
+		solve_penalty: *solve_penalty,
+		seed: None,
+		max_hints: 0,
+		hint_kind: HintKind::default(),
+		hint_penalty: 0.0,
+		time_limit: *time_limit,
+		evil: false,
+		boards: 1,
+		scoring: Scoring::default(),
+		daily_date: None,
+		alphabet: Alphabet {
+			language: *alphabet,
+			fold_accents: *fold_accents,
+		},
+	}
 }
 
-/// cleans up changes to the terminal environment we might have made inbetween,
-/// used to have an easier time handling ctrl-c or error paths.
-fn clean_exit(code: i32) -> ! {
+/// The rules of games with random words, and the words in the order they come
+/// up
+fn random_settings(
+	random: &RandomGame,
+) -> Result<(GameSettings, Vec<WordListEntry>), Box<dyn Error>> {
+	let RandomGame {
+		max_wrong_guesses,
+		solve_penalty,
+		alphabet,
+		fold_accents,
+		word_lists,
+		with_builtin,
+		seed,
+		max_hints,
+		hint_kind,
+		hint_penalty,
+		time_limit,
+		boards,
+		..
+	} = random;
+
+	let alphabet = Alphabet {
+		language: *alphabet,
+		fold_accents: *fold_accents,
+	};
+
+	let mut word_list = Vec::new();
+	if word_lists.is_empty() || *with_builtin {
+		word_list.extend(builtin_word_list());
+	}
+	for path in word_lists {
+		word_list.extend(read_word_list(path)?);
+	}
+
+	let seed = seed.unwrap_or_else(random_seed);
+	let words = get_filtered_word_list(&word_list, &random.word_filter(), &alphabet, seed);
+	let settings = GameSettings {
+		word_source: WordSource::Random,
+		max_wrong_guesses: *max_wrong_guesses,
+		solve_penalty: *solve_penalty,
+		alphabet,
+		seed: Some(seed),
+		max_hints: *max_hints,
+		hint_kind: *hint_kind,
+		hint_penalty: *hint_penalty,
+		time_limit: *time_limit,
+		evil: false,
+		boards: *boards,
+		scoring: Scoring::default(),
+		daily_date: None,
+	};
+	Ok((settings, words))
+}
+
+/// The scoring rule of that kind, with the parameters of the scoring config
+fn load_scoring(kind: ScoringKind) -> Result<Scoring, StorageError> {
+	let config = ScoringConfig::load(&ScoringConfig::default_path()?)?;
+	Ok(config.scoring(kind))
+}
+
+/// Sets up the game that was asked for on the command line
+fn new_game(args: &HangmanCliOptions) -> Result<(Session, GameState), Box<dyn Error>> {
+	let session = |filter: Option<WordFilter>| Session {
+		player: args.player_name(),
+		mode: args.mode.name().to_string(),
+		filter,
+	};
+
+	match &args.mode {
+		GameMode::Manual(manual) => {
+			let mut settings = manual_settings(manual);
+			settings.scoring = load_scoring(args.scoring)?;
+			Ok((session(None), GameState::new(settings, Vec::new())))
+		}
+		GameMode::Versus(VersusGame { rules, players }) => {
+			let mut settings = manual_settings(rules);
+			settings.scoring = load_scoring(args.scoring)?;
+			Ok((
+				session(None),
+				GameState::new_versus(settings, players.clone()),
+			))
+		}
+		GameMode::Random(random) | GameMode::Evil(random) => {
+			let (mut settings, words) = random_settings(random)?;
+			settings.evil = matches!(args.mode, GameMode::Evil(_));
+			settings.scoring = load_scoring(args.scoring)?;
+			Ok((
+				session(Some(random.word_filter())),
+				GameState::new(settings, words),
+			))
+		}
+		GameMode::Daily(DailyGame {}) => {
+			let today = Local::now().date_naive();
+			let player = args.player_name();
+			let path = DailyResults::default_path()?;
+			let mut results = DailyResults::load(&path)?;
+
+			if let Some(result) = results.get(today, &player) {
+				let mut message = format!("You already played the daily challenge of {}", today);
+				if let Some(outcome) = &result.outcome {
+					message.push_str(&format!("\n\n{}", outcome.summary));
+				}
+				return Err(message.into());
+			}
+
+			let word = daily_word(&builtin_word_list(), today)
+				.ok_or("The built-in word list has no word for the daily challenge")?;
+
+			// quitting halfway counts as having played, no peeking at the word
+			// and trying again
+			results.set(DailyResult {
+				date: today,
+				player,
+				outcome: None,
+			});
+			results.save(&path)?;
+
+			Ok((
+				session(Some(DAILY_FILTER)),
+				GameState::new(daily_settings(today), vec![word]),
+			))
+		}
+		GameMode::Resume(ResumeGame { slot }) => match SavedGame::take(slot)? {
+			Some(SavedGame { session, state, .. }) => Ok((session, state)),
+			None => Err(format!("There is no saved game in slot \"{}\"", slot).into()),
+		},
+		GameMode::Scores(_)
+		| GameMode::Host(_)
+		| GameMode::Join(_)
+		| GameMode::Solve(_)
+		| GameMode::Reverse(_)
+		| GameMode::Replay(_) => {
+			unreachable!("not a game played in this terminal alone")
+		}
+	}
+}
+
+/// Runs the game, and records it if there is a recording. Returns the error
+/// that stopped the recording, if the game didn't make it into the file
+/// completely.
+fn play_game<F: Frontend>(
+	mut frontend: F,
+	state: GameState,
+	recording: Option<RecordingFile>,
+) -> Result<Option<StorageError>, DriverError<F::Error>> {
+	match recording {
+		Some(recording) => {
+			let mut recorder = Recorder::new(frontend, recording);
+			driver::run(&mut recorder, state)?;
+			Ok(recorder.finish().err())
+		}
+		None => {
+			driver::run(&mut frontend, state)?;
+			Ok(None)
+		}
+	}
+}
+
+fn host(options: &HostedGame, scoring: ScoringKind) -> Result<(), Box<dyn Error>> {
+	let (mut settings, words) = random_settings(&options.rules)?;
+	settings.scoring = load_scoring(scoring)?;
+	if words.is_empty() {
+		return Err(ValidationError::NoWordsFound.into());
+	}
+	host_game(options.port, HostGame::new(options.mode, settings, words))?;
+	Ok(())
+}
+
+fn solve(options: &SolveGame) -> Result<(), Box<dyn Error>> {
+	let (settings, words) = random_settings(&options.rules)?;
+	if words.is_empty() {
+		return Err(ValidationError::NoWordsFound.into());
+	}
+
+	let mut frontend = AutoplayFrontend {
+		solver: Solver::new(options.strategy, &words, settings.alphabet),
+		rounds: options.rounds,
+		verbose: !options.quiet,
+		stats: SolverStats::default(),
+	};
+	let state = match driver::run(&mut frontend, GameState::new(settings, words)) {
+		Ok(state) => state,
+		Err(DriverError::Transition(error)) => return Err(error.into()),
+		Err(DriverError::Frontend(never)) => match never {},
+	};
+
+	println!("Strategy:       {}", options.strategy);
+	frontend.stats.print();
+	if let Some(seed) = state.settings.seed {
+		println!("Seed:           {}", seed);
+	}
+	Ok(())
+}
+
+/// Sets up a reverse game, with a solver that knows the words of the list
+fn reverse_game(
+	options: &ReverseGame,
+	scoring: ScoringKind,
+) -> Result<(Solver, GameState), Box<dyn Error>> {
+	let (mut settings, words) = random_settings(&options.rules)?;
+	if words.is_empty() {
+		return Err(ValidationError::NoWordsFound.into());
+	}
+	settings.word_source = WordSource::Reverse;
+	settings.max_hints = 0;
+	settings.time_limit = None;
+	settings.scoring = load_scoring(scoring)?;
+
+	let solver = Solver::new(options.strategy, &words, settings.alphabet);
+	Ok((solver, GameState::new(settings, Vec::new())))
+}
+
+fn show_scores(options: &ShowScores) -> Result<(), StorageError> {
+	let high_scores = HighScores::load(&HighScores::default_path()?)?;
+	let mut top = high_scores.top(|entry| options.matches(entry));
+	top.truncate(options.count);
+	print_high_scores(&top);
+	Ok(())
+}
+
+/// undoes changes to the terminal environment we might have made inbetween.
+fn restore_terminal() {
+	// a closed terminal has nothing left to restore
+	if !has_terminal() {
+		return;
+	}
 	crossterm::execute!(
 		stdout(),
 		cursor::DisableBlinking,
@@ -38,6 +322,12 @@ fn clean_exit(code: i32) -> ! {
 	if terminal::is_raw_mode_enabled().unwrap() {
 		terminal::disable_raw_mode().unwrap();
 	}
+}
+
+/// cleans up changes to the terminal environment we might have made inbetween,
+/// used to have an easier time handling ctrl-c or error paths.
+fn clean_exit(code: i32) -> ! {
+	restore_terminal();
 	exit(code)
 }
 
@@ -45,6 +335,24 @@ fn terminate() -> ! {
 	clean_exit(1)
 }
 
+/// Switches to the screen the game is played on, makes Ctrl-C interrupt the
+/// next input and quits once the terminal is closed
+fn enter_game_screen() {
+	crossterm::execute!(stdout(), terminal::EnterAlternateScreen).unwrap();
+	watch_terminal();
+
+	ctrlc::set_handler(|| {
+		// the next input will be interrupted, unless Ctrl-C is pressed again
+		// before there is any
+		if QUIT_REQUESTED.swap(true, Ordering::SeqCst) {
+			terminate();
+		}
+	})
+	.expect("Error setting Ctrl-C handler");
+
+	println!("Welcome to Hangman!");
+}
+
 fn reset_screen() -> crossterm::Result<()> {
 	crossterm::execute!(
 		stdout(),
@@ -61,22 +369,211 @@ fn main() -> ! {
 			Ok(parsed)
 		});
 
-	match parsed {
+	let args = match parsed {
+		Err(error) => {
+			eprintln!("{}", error);
+			exit(1)
+		}
+		Ok(args) => args,
+	};
+
+	if let GameMode::Scores(options) = &args.mode {
+		if args.protocol != Protocol::Terminal {
+			eprintln!("High scores can only be shown in the terminal");
+			exit(1)
+		}
+		if let Err(error) = show_scores(options) {
+			eprintln!("{}", error);
+			exit(1)
+		}
+		exit(0)
+	}
+
+	if let GameMode::Solve(options) = &args.mode {
+		if args.protocol != Protocol::Terminal {
+			eprintln!("The solver can only be watched in the terminal");
+			exit(1)
+		}
+		if let Err(error) = solve(options) {
+			eprintln!("{}", error);
+			exit(1)
+		}
+		exit(0)
+	}
+
+	if let GameMode::Host(options) = &args.mode {
+		if args.protocol != Protocol::Terminal {
+			eprintln!("Games over the network can only be hosted in the terminal");
+			exit(1)
+		}
+		if let Err(error) = host(options, args.scoring) {
+			eprintln!("{}", error);
+			exit(1)
+		}
+		exit(0)
+	}
+
+	let theme = match load_theme(&args.theme) {
+		Err(error) => {
+			eprintln!("{}", error);
+			exit(1)
+		}
+		Ok(theme) => theme,
+	};
+
+	if let GameMode::Join(JoinGame { address }) = &args.mode {
+		if args.protocol != Protocol::Terminal {
+			eprintln!("Games over the network can only be joined in the terminal");
+			exit(1)
+		}
+		let player = args.player_name();
+		let connection = match Connection::join(address, &player) {
+			Err(error) => {
+				eprintln!("{}", error);
+				exit(1)
+			}
+			Ok(connection) => connection,
+		};
+		let session = Session {
+			player,
+			mode: connection.mode.name().to_string(),
+			filter: None,
+		};
+
+		enter_game_screen();
+		let frontend = TerminalFrontend {
+			session,
+			theme,
+			replay: false,
+			has_rendered: false,
+		};
+		if let Err(error) = play(connection, frontend) {
+			restore_terminal();
+			eprintln!("{}", error);
+			exit(1)
+		}
+		clean_exit(0)
+	}
+
+	if let GameMode::Reverse(options) = &args.mode {
+		if args.protocol != Protocol::Terminal {
+			eprintln!("Reverse games can only be played in the terminal");
+			exit(1)
+		}
+		let (solver, state) = match reverse_game(options, args.scoring) {
+			Err(error) => {
+				eprintln!("{}", error);
+				exit(1)
+			}
+			Ok(game) => game,
+		};
+
+		enter_game_screen();
+		let mut frontend = ReverseFrontend::new(solver, theme);
+		match driver::run(&mut frontend, state) {
+			Err(DriverError::Frontend(error)) if is_interrupted(&error) => terminate(),
+			Err(DriverError::Frontend(error)) if is_terminal_closed(&error) => exit(1),
+			Err(error) => {
+				restore_terminal();
+				eprintln!("{}", error);
+				exit(1)
+			}
+			Ok(_) => (),
+		}
+		clean_exit(0)
+	}
+
+	if let GameMode::Replay(ReplayGame { file, speed }) = &args.mode {
+		if args.protocol != Protocol::Terminal {
+			eprintln!("Recordings can only be played back in the terminal");
+			exit(1)
+		}
+		let recording = match Recording::load(file) {
+			Err(error) => {
+				eprintln!("{}", error);
+				exit(1)
+			}
+			Ok(recording) => recording,
+		};
+
+		enter_game_screen();
+		match replay(recording, theme, *speed) {
+			Err(error) if is_interrupted(&error) => terminate(),
+			Err(error) if is_terminal_closed(&error) => exit(1),
+			Err(error) => {
+				restore_terminal();
+				eprintln!("{}", error);
+				exit(1)
+			}
+			Ok(()) => (),
+		}
+		clean_exit(0)
+	}
+
+	// the result of the day is only kept by the terminal game, and the day
+	// counts as played right away
+	if matches!(args.mode, GameMode::Daily(_)) && args.protocol != Protocol::Terminal {
+		eprintln!("The daily challenge can only be played in the terminal");
+		exit(1)
+	}
+
+	let (session, state) = match new_game(&args) {
 		Err(error) => {
 			eprintln!("{}", error);
 			exit(1)
 		}
-		Ok(args) => {
-			crossterm::execute!(stdout(), terminal::EnterAlternateScreen).unwrap();
+		Ok(game) => game,
+	};
 
-			ctrlc::set_handler(|| {
-				terminate();
-			})
-			.expect("Error setting Ctrl-C handler");
+	let recording = match &args.record {
+		Some(path) => match RecordingFile::create(path, &session) {
+			Err(error) => {
+				eprintln!("{}", error);
+				exit(1)
+			}
+			Ok(recording) => Some(recording),
+		},
+		None => None,
+	};
 
-			println!("Welcome to Hangman!");
-			hangman_game(args);
-			clean_exit(0)
+	if args.protocol == Protocol::Jsonl {
+		let stdin = io::stdin();
+		let frontend = JsonlFrontend::new(stdin.lock(), io::stdout());
+		match play_game(frontend, state, recording) {
+			Err(error) => {
+				eprintln!("{}", error);
+				exit(1)
+			}
+			Ok(Some(error)) => {
+				eprintln!("The game could not be recorded: {}", error);
+				exit(1)
+			}
+			Ok(None) => exit(0),
+		}
+	}
+
+	enter_game_screen();
+	let frontend = TerminalFrontend {
+		session,
+		theme,
+		replay: false,
+		has_rendered: false,
+	};
+	match play_game(frontend, state, recording) {
+		Err(DriverError::Frontend(error)) if is_interrupted(&error) => terminate(),
+		// nobody is left to show the error to
+		Err(DriverError::Frontend(error)) if is_terminal_closed(&error) => exit(1),
+		Err(error) => {
+			restore_terminal();
+			eprintln!("{}", error);
+			exit(1)
+		}
+		Ok(Some(error)) => {
+			restore_terminal();
+			eprintln!("The game could not be recorded: {}", error);
+			exit(1)
 		}
+		Ok(None) => (),
 	}
+	clean_exit(0)
 }
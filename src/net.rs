@@ -0,0 +1,433 @@
+use crate::errors::NetError;
+use crate::game_state::{GameAction, GameScene, GameSettings, GameState, GuessFeedback};
+use crate::word_list::WordListEntry;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::io::{BufRead, Write};
+
+/// The port hosts listen on when no other one is picked
+pub const DEFAULT_PORT: u16 = 7878;
+
+/// How the players of a networked game play together
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NetMode {
+	/// Everybody guesses the same word together, every guess counts for all
+	Coop,
+	/// Everybody guesses the same words on their own, and gets their own score
+	Race,
+}
+
+crate::named_enum!(NetMode, "network mode", {
+	Coop => "coop",
+	Race => "race",
+});
+
+/// What clients send to the host
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+	/// The first message of every client
+	Join {
+		name: String,
+	},
+	/// Something the player did, see [HostGame::apply]
+	Action {
+		action: GameAction,
+	},
+	Leave,
+}
+
+/// What the host sends to clients
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HostMessage {
+	/// The answer to a [ClientMessage::Join] that was accepted
+	Welcome { mode: NetMode },
+	/// The game as the player sees it, see [masked]
+	Update {
+		state: Box<GameState>,
+		players: Vec<NetPlayer>,
+		/// Who made the last move, in co-op games
+		last_player: Option<String>,
+	},
+	/// The last message could not be accepted
+	Rejected { reason: String },
+}
+
+/// How a player of a networked game is doing
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetPlayer {
+	pub name: String,
+	pub score: f64,
+	pub rounds_played: u32,
+}
+
+/// Sends a message as a single line of JSON
+pub fn send<T: Serialize>(writer: &mut impl Write, message: &T) -> Result<(), NetError> {
+	let mut line = serde_json::to_string(message).map_err(NetError::Json)?;
+	line.push('\n');
+	writer.write_all(line.as_bytes()).map_err(NetError::Io)?;
+	writer.flush().map_err(NetError::Io)
+}
+
+/// Waits for the next line and reads the message on it, `None` once the
+/// other side closed the connection
+pub fn receive<T: DeserializeOwned>(reader: &mut impl BufRead) -> Result<Option<T>, NetError> {
+	let mut line = String::new();
+	if reader.read_line(&mut line).map_err(NetError::Io)? == 0 {
+		return Ok(None);
+	}
+
+	serde_json::from_str(line.trim_end())
+		.map(Some)
+		.map_err(|error| NetError::BadMessage {
+			line: line.trim_end().to_string(),
+			error,
+		})
+}
+
+/// The game as players may see it: the letters that are still hidden are
+/// masked (see [crate::game_state::Round::masked_word]), and the words of the next rounds are left out.
+/// The word is only shown in full once the round is over.
+pub fn masked(state: &GameState) -> GameState {
+	let mut state = state.clone();
+	state.unplayed_words.clear();
+	match &mut state.scene {
+		GameScene::AwaitingGuess { round, .. }
+		| GameScene::ValidGuess { round, .. }
+		| GameScene::ValidSolve { round, .. } => {
+			round.word = round.masked_word();
+		}
+		GameScene::Init | GameScene::RoundEnd { .. } | GameScene::GameEnd { .. } => (),
+	}
+	state
+}
+
+/// How a host tells its clients apart
+pub type ClientId = u64;
+
+struct HostPlayer {
+	id: ClientId,
+	name: String,
+	/// The player's own game in race mode. In co-op mode everybody plays
+	/// [HostGame::game] instead.
+	game: GameState,
+}
+
+/// Everything a host keeps track of, without the networking: who joined, and
+/// the games they play. The host has the only real [GameState]s, clients
+/// just send their actions and show what they get back.
+pub struct HostGame {
+	mode: NetMode,
+	/// The words of all rounds, the one of the first round last like in
+	/// [GameState::unplayed_words]
+	words: Vec<WordListEntry>,
+	/// The game everybody plays in co-op mode. In race mode, it's the fresh
+	/// game players start with when they join.
+	game: GameState,
+	players: Vec<HostPlayer>,
+	last_player: Option<String>,
+}
+
+impl HostGame {
+	/// `settings` should be for [crate::game_state::WordSource::Random]
+	/// games, as all words are drawn from `words`
+	pub fn new(mode: NetMode, settings: GameSettings, words: Vec<WordListEntry>) -> HostGame {
+		let first_word = words.last().cloned().into_iter().collect();
+		HostGame {
+			mode,
+			game: GameState::new(settings, first_word),
+			words,
+			players: Vec::new(),
+			last_player: None,
+		}
+	}
+
+	pub fn mode(&self) -> NetMode {
+		self.mode
+	}
+
+	pub fn player_name(&self, id: ClientId) -> Option<&str> {
+		self.player(id).map(|player| player.name.as_str())
+	}
+
+	/// Everybody who joined, in the order they did
+	pub fn client_ids(&self) -> Vec<ClientId> {
+		self.players.iter().map(|player| player.id).collect()
+	}
+
+	/// The players, the best first. In co-op mode they all share the score
+	/// of the game.
+	pub fn standings(&self) -> Vec<NetPlayer> {
+		let mut standings: Vec<NetPlayer> = self
+			.players
+			.iter()
+			.map(|player| {
+				let game = self.game_of(player);
+				NetPlayer {
+					name: player.name.clone(),
+					score: game.score,
+					rounds_played: game.rounds_played,
+				}
+			})
+			.collect();
+		standings.sort_by(|a, b| b.score.total_cmp(&a.score));
+		standings
+	}
+
+	pub fn join(&mut self, id: ClientId, name: String) -> Result<(), NetError> {
+		if self.players.iter().any(|player| player.name == name) {
+			return Err(NetError::NameTaken(name));
+		}
+		self.players.push(HostPlayer {
+			id,
+			name,
+			game: self.game.clone(),
+		});
+		Ok(())
+	}
+
+	pub fn leave(&mut self, id: ClientId) {
+		self.players.retain(|player| player.id != id);
+	}
+
+	/// Applies an action of a player to their game, and returns who has to
+	/// be sent an update because of it. Guesses are made right away, players
+	/// can only start, play and move on to rounds. Leaving a game is up to
+	/// the client, see [ClientMessage::Leave].
+	pub fn apply(&mut self, id: ClientId, action: GameAction) -> Result<Vec<ClientId>, NetError> {
+		match action {
+			GameAction::StartRandomGame
+			| GameAction::InputGuess(_)
+			| GameAction::InputSolve(_)
+			| GameAction::TakeHint
+			| GameAction::TimeUp
+			| GameAction::NewRound => (),
+			action => return Err(NetError::NotAllowed(format!("{:?}", action))),
+		}
+
+		let before = self.standings();
+		let words = &self.words;
+		let mode = self.mode;
+		let player = self
+			.players
+			.iter_mut()
+			.find(|player| player.id == id)
+			.ok_or(NetError::UnknownPlayer)?;
+		let name = player.name.clone();
+		let game = match mode {
+			NetMode::Coop => &mut self.game,
+			NetMode::Race => &mut player.game,
+		};
+
+		if let GameAction::StartRandomGame = action {
+			// every game only gets the word of the round it starts, so all
+			// games of a race get the same ones, and the state sent around
+			// stays small
+			game.unplayed_words = (words.len())
+				.checked_sub(game.rounds_played as usize + 1)
+				.map(|index| words[index].clone())
+				.into_iter()
+				.collect();
+		}
+
+		// the game only changes if the action fits its scene, as clients
+		// might still be looking at an older one
+		let mut next = game.clone().apply(action).map_err(NetError::Transition)?;
+		loop {
+			let action = match next.scene {
+				GameScene::ValidGuess { .. } => GameAction::MakeGuess,
+				GameScene::ValidSolve { .. } => GameAction::MakeSolve,
+				_ => break,
+			};
+			next = next.apply(action).map_err(NetError::Transition)?;
+		}
+		// feedback on a guess that changed nothing, only the player who made
+		// it needs to see it
+		let changed_nothing = matches!(
+			next.scene,
+			GameScene::AwaitingGuess {
+				feedback: GuessFeedback::AlreadyTried(_)
+					| GuessFeedback::BadChar(_)
+					| GuessFeedback::NoHintsLeft
+					| GuessFeedback::BadSolve,
+				..
+			}
+		);
+		*game = next;
+
+		let concerns_everybody = match mode {
+			NetMode::Coop => !changed_nothing,
+			NetMode::Race => self.standings() != before,
+		};
+		if mode == NetMode::Coop && concerns_everybody {
+			self.last_player = Some(name);
+		}
+
+		if concerns_everybody {
+			Ok(self.client_ids())
+		} else {
+			Ok(vec![id])
+		}
+	}
+
+	/// What the player is shown of the game, `None` if they didn't join
+	pub fn update_for(&self, id: ClientId) -> Option<HostMessage> {
+		let player = self.player(id)?;
+		Some(HostMessage::Update {
+			state: Box::new(masked(self.game_of(player))),
+			players: self.standings(),
+			last_player: self.last_player.clone(),
+		})
+	}
+
+	fn player(&self, id: ClientId) -> Option<&HostPlayer> {
+		self.players.iter().find(|player| player.id == id)
+	}
+
+	fn game_of<'a>(&'a self, player: &'a HostPlayer) -> &'a GameState {
+		match self.mode {
+			NetMode::Coop => &self.game,
+			NetMode::Race => &player.game,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::game_state::EndFeedback;
+
+	fn entry(phrase: &str) -> WordListEntry {
+		WordListEntry::new(phrase, 50)
+	}
+
+	/// A game with "cat" as the first word and "dog" as the second, that
+	/// Alice (1) and Bob (2) joined
+	fn host_game(mode: NetMode) -> HostGame {
+		let mut game = HostGame::new(
+			mode,
+			GameSettings::default(),
+			vec![entry("dog"), entry("cat")],
+		);
+		game.join(1, "Alice".to_string()).unwrap();
+		game.join(2, "Bob".to_string()).unwrap();
+		game
+	}
+
+	fn game_of(game: &HostGame, id: ClientId) -> &GameState {
+		game.game_of(game.player(id).unwrap())
+	}
+
+	/// The word of the player's round, in full
+	fn word_of(game: &HostGame, id: ClientId) -> Option<&str> {
+		match &game_of(game, id).scene {
+			GameScene::AwaitingGuess { round, .. } | GameScene::RoundEnd { round, .. } => {
+				Some(&round.word)
+			}
+			_ => None,
+		}
+	}
+
+	fn guess(game: &mut HostGame, id: ClientId, letter: char) -> Vec<ClientId> {
+		game.apply(id, GameAction::InputGuess(Some(letter)))
+			.unwrap()
+	}
+
+	#[test]
+	fn coop_guesses_count_for_everybody() {
+		let mut game = host_game(NetMode::Coop);
+
+		assert_eq!(game.apply(1, GameAction::StartRandomGame).unwrap(), [1, 2]);
+		assert_eq!(guess(&mut game, 2, 'c'), [1, 2]);
+		assert_eq!(game.last_player.as_deref(), Some("Bob"));
+		assert!(std::ptr::eq(game_of(&game, 1), game_of(&game, 2)));
+
+		// only the player who tried a letter again is told so
+		assert_eq!(guess(&mut game, 1, 'c'), [1]);
+		assert_eq!(game.last_player.as_deref(), Some("Bob"));
+	}
+
+	#[test]
+	fn race_players_only_see_how_the_others_are_doing() {
+		let mut game = host_game(NetMode::Race);
+
+		// starting a round counts as playing it, which shows in the standings
+		assert_eq!(game.apply(1, GameAction::StartRandomGame).unwrap(), [1, 2]);
+		assert_eq!(guess(&mut game, 1, 'c'), [1]);
+		assert_eq!(guess(&mut game, 1, 'a'), [1]);
+		assert!(matches!(game_of(&game, 2).scene, GameScene::Init));
+
+		// winning the round changes the standings, which everybody sees
+		assert_eq!(guess(&mut game, 1, 't'), [1, 2]);
+		assert!(game_of(&game, 1).score > 0.0);
+		assert_eq!(game_of(&game, 2).score, 0.0);
+		assert_eq!(game.last_player, None);
+	}
+
+	#[test]
+	fn race_players_get_the_words_in_the_same_order() {
+		let mut game = host_game(NetMode::Race);
+
+		game.apply(1, GameAction::StartRandomGame).unwrap();
+		for letter in ['c', 'a', 't'] {
+			guess(&mut game, 1, letter);
+		}
+		game.apply(1, GameAction::NewRound).unwrap();
+		game.apply(1, GameAction::StartRandomGame).unwrap();
+		game.apply(2, GameAction::StartRandomGame).unwrap();
+
+		assert_eq!(word_of(&game, 1), Some("dog"));
+		assert_eq!(word_of(&game, 2), Some("cat"));
+	}
+
+	#[test]
+	fn masked_hides_the_letters_and_words_to_come() {
+		let mut game = host_game(NetMode::Coop);
+		game.apply(1, GameAction::StartRandomGame).unwrap();
+		guess(&mut game, 1, 'c');
+		guess(&mut game, 1, 'x');
+
+		let state = game_of(&game, 1).clone();
+		let masked_state = masked(&state);
+		match &masked_state.scene {
+			GameScene::AwaitingGuess { round, .. } => assert_eq!(round.word, "c__"),
+			scene => panic!("unexpected scene {}", scene.name()),
+		}
+		assert!(masked_state.unplayed_words.is_empty());
+
+		guess(&mut game, 1, 'a');
+		guess(&mut game, 1, 't');
+		match &masked(game_of(&game, 1)).scene {
+			GameScene::RoundEnd { round, .. } => assert_eq!(round.word, "cat"),
+			scene => panic!("unexpected scene {}", scene.name()),
+		}
+	}
+
+	#[test]
+	fn players_can_only_take_their_own_actions() {
+		let mut game = host_game(NetMode::Coop);
+		game.apply(1, GameAction::StartRandomGame).unwrap();
+
+		let host_only = [
+			GameAction::EndGame(EndFeedback::ManuallyEnded),
+			GameAction::StartManualGame("dog".to_string()),
+			GameAction::MakeGuess,
+			GameAction::MakeSolve,
+		];
+		for action in host_only {
+			assert!(matches!(
+				game.apply(1, action),
+				Err(NetError::NotAllowed(_))
+			));
+		}
+		assert!(matches!(
+			game.apply(3, GameAction::TakeHint),
+			Err(NetError::UnknownPlayer)
+		));
+		assert!(matches!(
+			game_of(&game, 1).scene,
+			GameScene::AwaitingGuess { .. }
+		));
+	}
+}
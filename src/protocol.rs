@@ -0,0 +1,301 @@
+use hangman::driver::Frontend;
+use hangman::game_state::{
+	EndFeedback, GameAction, GameScene, GameState, GuessFeedback, Player, Round, RoundRecord,
+	WordSource,
+};
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead, Write};
+
+/// How the game talks to whoever plays it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Protocol {
+	/// The interactive game, with keypresses and drawings
+	Terminal,
+	/// A JSON object per line, see [JsonlFrontend]
+	Jsonl,
+}
+
+hangman::named_enum!(Protocol, "protocol", {
+	Terminal => "terminal",
+	Jsonl => "jsonl",
+});
+
+/// What scripts send, one per line
+#[derive(Debug, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+enum Command {
+	Guess {
+		letter: char,
+	},
+	Solve {
+		phrase: String,
+	},
+	Hint,
+	/// Starts the first or the next round. Manual games need the word to
+	/// guess.
+	Next {
+		#[serde(default)]
+		word: Option<String>,
+	},
+	Quit,
+}
+
+impl Command {
+	fn name(&self) -> &'static str {
+		match self {
+			Command::Guess { .. } => "guess",
+			Command::Solve { .. } => "solve",
+			Command::Hint => "hint",
+			Command::Next { .. } => "next",
+			Command::Quit => "quit",
+		}
+	}
+}
+
+/// What is written for every scene
+#[derive(Debug, Serialize)]
+struct SceneReport<'a> {
+	/// See [GameScene::name]
+	scene: &'static str,
+	/// The word with hidden letters masked, in full once the round is over
+	#[serde(skip_serializing_if = "Option::is_none")]
+	word: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	category: Option<&'a str>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	clue: Option<&'a str>,
+	/// The letter keys that were guessed on any board, in alphabetical order
+	guessed: Vec<char>,
+	wrong_guesses: usize,
+	max_wrong_guesses: u8,
+	hints_left: usize,
+	/// Seconds left in games with a time limit
+	#[serde(skip_serializing_if = "Option::is_none")]
+	time_left: Option<f64>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	feedback: Option<&'a GuessFeedback>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	won: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	round_score: Option<f64>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	end: Option<&'a EndFeedback>,
+	score: f64,
+	rounds_played: u32,
+	/// The players of versus games
+	#[serde(skip_serializing_if = "<[_]>::is_empty")]
+	players: &'a [Player],
+	/// Every board of rounds with several of them, `word` and
+	/// `wrong_guesses` are the ones of the first board
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	boards: Vec<BoardReport>,
+	/// How every round went, once the game is over
+	#[serde(skip_serializing_if = "Option::is_none")]
+	records: Option<&'a [RoundRecord]>,
+}
+
+/// A single board of a round with several of them
+#[derive(Debug, Serialize)]
+struct BoardReport {
+	/// The word with hidden letters masked, in full once the round is over
+	word: String,
+	wrong_guesses: usize,
+	solved: bool,
+}
+
+impl<'a> SceneReport<'a> {
+	fn new(state: &'a GameState) -> SceneReport<'a> {
+		let mut report = SceneReport {
+			scene: state.scene.name(),
+			word: None,
+			category: None,
+			clue: None,
+			guessed: Vec::new(),
+			wrong_guesses: 0,
+			max_wrong_guesses: state.settings.max_wrong_guesses,
+			hints_left: state.settings.max_hints.into(),
+			time_left: state.time_left().map(|left| left.as_secs_f64()),
+			feedback: None,
+			won: None,
+			round_score: None,
+			end: None,
+			score: state.score,
+			rounds_played: state.rounds_played,
+			players: &state.players,
+			boards: Vec::new(),
+			records: None,
+		};
+
+		let round = match &state.scene {
+			GameScene::Init => None,
+			GameScene::AwaitingGuess { round, feedback } => {
+				report.feedback = Some(feedback);
+				Some(round)
+			}
+			GameScene::ValidGuess { round, .. } | GameScene::ValidSolve { round, .. } => {
+				Some(round)
+			}
+			GameScene::RoundEnd {
+				round,
+				won,
+				round_score,
+			} => {
+				report.won = Some(*won);
+				report.round_score = Some(*round_score);
+				Some(round)
+			}
+			GameScene::GameEnd { feedback } => {
+				report.end = Some(feedback);
+				report.records = Some(&state.records);
+				None
+			}
+		};
+
+		if let Some(round) = round {
+			let is_over = matches!(state.scene, GameScene::RoundEnd { .. });
+			let shown = |board: &Round| {
+				if is_over {
+					board.word.clone()
+				} else {
+					board.masked_word()
+				}
+			};
+			report.word = Some(shown(round));
+			if !round.boards.is_empty() {
+				report.boards = round
+					.all_boards()
+					.map(|board| BoardReport {
+						word: shown(board),
+						wrong_guesses: board.wrong_guesses(),
+						solved: board.is_solved(),
+					})
+					.collect();
+			}
+			report.category = round.category.as_deref();
+			report.clue = round.clue.as_deref();
+			report.guessed = round.guessed_letters().into_iter().collect();
+			report.guessed.sort_unstable();
+			report.wrong_guesses = round.wrong_guesses();
+			report.hints_left = report.hints_left.saturating_sub(round.hints_taken());
+		}
+		report
+	}
+}
+
+/// Written instead of a scene when a line could not be used
+#[derive(Debug, Serialize)]
+struct ErrorReport<'a> {
+	error: String,
+	scene: &'a str,
+}
+
+/// Plays the game through JSON lines, for scripts, test harnesses and bots.
+///
+/// Every scene is written to `output` as an object with the masked word, the
+/// guessed letters, feedback and scores. Commands are read from `input`:
+///
+/// - `{"command":"guess","letter":"e"}`
+/// - `{"command":"solve","phrase":"hangman"}`
+/// - `{"command":"hint"}`
+/// - `{"command":"next"}` starts a round, with `"word"` in manual games
+/// - `{"command":"quit"}`, the same as the end of the input
+///
+/// Lines that can't be used in the current scene are answered with an
+/// `{"error":...}` object. High scores are not recorded.
+pub struct JsonlFrontend<R, W> {
+	input: R,
+	output: W,
+}
+
+impl<R: BufRead, W: Write> JsonlFrontend<R, W> {
+	pub fn new(input: R, output: W) -> Self {
+		JsonlFrontend { input, output }
+	}
+
+	fn write_line<T: Serialize>(&mut self, value: &T) -> io::Result<()> {
+		serde_json::to_writer(&mut self.output, value)?;
+		writeln!(self.output)?;
+		self.output.flush()
+	}
+
+	/// The next command, `None` at the end of the input
+	fn read_command(&mut self, state: &GameState) -> io::Result<Option<Command>> {
+		loop {
+			let mut line = String::new();
+			if self.input.read_line(&mut line)? == 0 {
+				return Ok(None);
+			}
+			if line.trim().is_empty() {
+				continue;
+			}
+
+			match serde_json::from_str(&line) {
+				Ok(command) => return Ok(Some(command)),
+				Err(error) => self.write_line(&ErrorReport {
+					error: format!("Unknown command: {}", error),
+					scene: state.scene.name(),
+				})?,
+			}
+		}
+	}
+}
+
+impl<R: BufRead, W: Write> Frontend for JsonlFrontend<R, W> {
+	type Error = io::Error;
+
+	fn render(&mut self, state: &GameState) -> io::Result<()> {
+		self.write_line(&SceneReport::new(state))
+	}
+
+	fn next_action(&mut self, state: &GameState) -> io::Result<GameAction> {
+		match state.scene {
+			GameScene::ValidGuess { .. } => return Ok(GameAction::MakeGuess),
+			GameScene::ValidSolve { .. } => return Ok(GameAction::MakeSolve),
+			_ => (),
+		}
+
+		loop {
+			let command = match self.read_command(state)? {
+				Some(command) => command,
+				None => return Ok(GameAction::EndGame(EndFeedback::ManuallyEnded)),
+			};
+
+			let action = match (&state.scene, command) {
+				(_, Command::Quit) => Ok(GameAction::EndGame(EndFeedback::ManuallyEnded)),
+				(GameScene::Init, Command::Next { word }) => match state.settings.word_source {
+					WordSource::Random => Ok(GameAction::StartRandomGame),
+					WordSource::Manual => match word {
+						Some(word) if !word.trim().is_empty() => {
+							Ok(GameAction::StartManualGame(word))
+						}
+						_ => Err("Manual games need a \"word\" to start".to_string()),
+					},
+					WordSource::Reverse => {
+						Err("Reverse games can only be played in the terminal".to_string())
+					}
+				},
+				(GameScene::AwaitingGuess { .. }, Command::Guess { letter }) => {
+					Ok(GameAction::InputGuess(Some(letter)))
+				}
+				(GameScene::AwaitingGuess { .. }, Command::Solve { phrase }) => {
+					Ok(GameAction::InputSolve(phrase))
+				}
+				(GameScene::AwaitingGuess { .. }, Command::Hint) => Ok(GameAction::TakeHint),
+				(GameScene::RoundEnd { .. }, Command::Next { .. }) => Ok(GameAction::NewRound),
+				(scene, command) => Err(format!(
+					"\"{}\" can't be used in scene {}",
+					command.name(),
+					scene.name()
+				)),
+			};
+
+			match action {
+				Ok(action) => return Ok(action),
+				Err(error) => self.write_line(&ErrorReport {
+					error,
+					scene: state.scene.name(),
+				})?,
+			}
+		}
+	}
+}
@@ -0,0 +1,248 @@
+use crate::driver::Frontend;
+use crate::errors::StorageError;
+use crate::game_state::{GameAction, GameScene, GameState};
+use crate::saves::Session;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// The first line of every recording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingHeader {
+	pub session: Session,
+	pub started_at: DateTime<Utc>,
+}
+
+/// Something that happened in a recorded game, `millis` after it started
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum RecordedEvent {
+	/// What the frontend decided to do next, e.g. because of a key press
+	Input { millis: u64, action: GameAction },
+	/// The game as it was rendered after a transition. The first scene is
+	/// recorded in full, the later ones without the words of the next rounds
+	/// (see [GameState::unplayed_words]) and the words an evil round could
+	/// still switch to (see [crate::game_state::Round::candidates]) to keep
+	/// the file small.
+	Scene { millis: u64, state: Box<GameState> },
+}
+
+impl RecordedEvent {
+	pub fn millis(&self) -> u64 {
+		match self {
+			RecordedEvent::Input { millis, .. } | RecordedEvent::Scene { millis, .. } => *millis,
+		}
+	}
+}
+
+/// A whole game as it was written by a [Recorder]
+#[derive(Debug, Clone)]
+pub struct Recording {
+	pub header: RecordingHeader,
+	pub events: Vec<RecordedEvent>,
+}
+
+impl Recording {
+	/// Reads a recording, one JSON object per line
+	pub fn load(path: &Path) -> Result<Recording, StorageError> {
+		let contents = fs::read_to_string(path).map_err(|error| StorageError::io(path, error))?;
+		let mut lines = contents.lines().filter(|line| !line.trim().is_empty());
+		let parse_error = |error| StorageError::json(path, error);
+
+		let header = serde_json::from_str(lines.next().unwrap_or_default()).map_err(parse_error)?;
+		let events = lines
+			.map(serde_json::from_str)
+			.collect::<Result<_, _>>()
+			.map_err(parse_error)?;
+		Ok(Recording { header, events })
+	}
+}
+
+/// The file a [Recorder] writes to
+#[derive(Debug)]
+pub struct RecordingFile {
+	file: File,
+	path: PathBuf,
+	started: Instant,
+	/// Whether a scene was recorded yet, only the first one has all words
+	has_scenes: bool,
+	/// What stopped the recording, nothing is written after it
+	error: Option<StorageError>,
+}
+
+impl RecordingFile {
+	/// Starts a recording of the session, replacing whatever was in the file
+	/// before
+	pub fn create(path: &Path, session: &Session) -> Result<RecordingFile, StorageError> {
+		let mut recording = RecordingFile {
+			file: File::create(path).map_err(|error| StorageError::io(path, error))?,
+			path: path.to_path_buf(),
+			started: Instant::now(),
+			has_scenes: false,
+			error: None,
+		};
+		recording.write(&RecordingHeader {
+			session: session.clone(),
+			started_at: Utc::now(),
+		});
+		match recording.error.take() {
+			Some(error) => Err(error),
+			None => Ok(recording),
+		}
+	}
+
+	fn millis(&self) -> u64 {
+		u64::try_from(self.started.elapsed().as_millis()).unwrap_or(u64::MAX)
+	}
+
+	/// Writes a line right away, so a game that is quit with Ctrl-C is
+	/// recorded up to there
+	fn write<T: Serialize>(&mut self, value: &T) {
+		if self.error.is_some() {
+			return;
+		}
+
+		let result = serde_json::to_string(value)
+			.map_err(|error| StorageError::json(&self.path, error))
+			.and_then(|line| {
+				writeln!(self.file, "{}", line).map_err(|error| StorageError::io(&self.path, error))
+			});
+		if let Err(error) = result {
+			self.error = Some(error);
+		}
+	}
+}
+
+/// Wraps a frontend and records every scene it renders and every action it
+/// comes up with. A recording that fails halfway doesn't stop the game, see
+/// [Recorder::finish].
+pub struct Recorder<F> {
+	pub frontend: F,
+	recording: RecordingFile,
+}
+
+impl<F> Recorder<F> {
+	pub fn new(frontend: F, recording: RecordingFile) -> Recorder<F> {
+		Recorder {
+			frontend,
+			recording,
+		}
+	}
+
+	/// Ends the recording. Returns the error that stopped it, if the game
+	/// didn't make it into the file completely.
+	pub fn finish(self) -> Result<F, StorageError> {
+		match self.recording.error {
+			Some(error) => Err(error),
+			None => Ok(self.frontend),
+		}
+	}
+}
+
+impl<F: Frontend> Frontend for Recorder<F> {
+	type Error = F::Error;
+
+	fn render(&mut self, state: &GameState) -> Result<(), F::Error> {
+		let mut recorded = state.clone();
+		if self.recording.has_scenes {
+			recorded.unplayed_words.clear();
+			if let GameScene::AwaitingGuess { round, .. }
+			| GameScene::ValidGuess { round, .. }
+			| GameScene::ValidSolve { round, .. }
+			| GameScene::RoundEnd { round, .. } = &mut recorded.scene
+			{
+				round.candidates.clear();
+				for board in &mut round.boards {
+					board.candidates.clear();
+				}
+			}
+		}
+		self.recording.has_scenes = true;
+		let millis = self.recording.millis();
+		self.recording.write(&RecordedEvent::Scene {
+			millis,
+			state: Box::new(recorded),
+		});
+
+		self.frontend.render(state)
+	}
+
+	fn next_action(&mut self, state: &GameState) -> Result<GameAction, F::Error> {
+		let action = self.frontend.next_action(state)?;
+		let millis = self.recording.millis();
+		self.recording.write(&RecordedEvent::Input {
+			millis,
+			action: action.clone(),
+		});
+		Ok(action)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::game_state::GameSettings;
+	use crate::word_list::WordListEntry;
+	use std::convert::Infallible;
+
+	/// Shows nothing, the recording is all that matters
+	struct Blank;
+
+	impl Frontend for Blank {
+		type Error = Infallible;
+
+		fn render(&mut self, _state: &GameState) -> Result<(), Infallible> {
+			Ok(())
+		}
+
+		fn next_action(&mut self, _state: &GameState) -> Result<GameAction, Infallible> {
+			Ok(GameAction::StartRandomGame)
+		}
+	}
+
+	#[test]
+	fn later_scenes_leave_out_the_candidates() {
+		let path = std::env::temp_dir().join(format!("hangman-test-{}.jsonl", std::process::id()));
+		let session = Session {
+			player: "Alice".to_string(),
+			mode: "random".to_string(),
+			filter: None,
+		};
+		let mut recorder = Recorder::new(Blank, RecordingFile::create(&path, &session).unwrap());
+
+		let words = ["cat", "cot", "cut"]
+			.iter()
+			.map(|phrase| WordListEntry::new(phrase, 50))
+			.collect();
+		let settings = GameSettings {
+			evil: true,
+			..GameSettings::default()
+		};
+		let state = GameState::new(settings, words);
+		recorder.render(&state).unwrap();
+		let state = state.start_random_game().unwrap();
+		recorder.render(&state).unwrap();
+		recorder.finish().unwrap();
+
+		let recording = Recording::load(&path).unwrap();
+		fs::remove_file(&path).unwrap();
+		match (&state.scene, &recording.events[1]) {
+			(
+				GameScene::AwaitingGuess { round: played, .. },
+				RecordedEvent::Scene {
+					state: recorded, ..
+				},
+			) => {
+				assert_eq!(played.candidates.len(), 3);
+				match &recorded.scene {
+					GameScene::AwaitingGuess { round, .. } => assert!(round.candidates.is_empty()),
+					scene => panic!("unexpected scene {}", scene.name()),
+				}
+			}
+			_ => panic!("the round wasn't recorded"),
+		}
+	}
+}
@@ -1,26 +1,59 @@
-use crate::art;
-use crate::game_state::{EndFeedback, GameScene, GameState, GuessFeedback};
-use crate::input::{confirm_enter, confirm_yn, get_char, get_word};
+use crate::art::{Palette, Theme};
+use crate::input::{confirm_enter, confirm_yn, get_char, get_word, is_interrupted, is_timed_out};
 use crate::reset_screen;
-use crate::validation::{GameMode, ManualGame, RandomGame};
+use hangman::alphabet::{display_letter, Alphabet};
+use hangman::daily::{share_summary, DailyOutcome, DailyResult, DailyResults, DAILY_MODE};
+use hangman::driver::Frontend;
+use hangman::errors::StorageError;
+use hangman::game_state::{
+	EndFeedback, GameAction, GameScene, GameSettings, GameState, Guess, GuessFeedback, Round,
+	RoundRecord, WordSource,
+};
+use hangman::high_scores::{HighScoreEntry, HighScores};
+use hangman::net::NetMode;
+use hangman::saves::{SavedGame, Session, DEFAULT_SLOT};
 
-use crossterm::style::Stylize;
+use chrono::{Local, NaiveDate, Utc};
+
+use crossterm::style::{ContentStyle, Stylize};
+use crossterm::terminal;
 use itertools::Itertools;
 use std::collections::HashSet;
 use std::io::{stdout, Write};
+use std::mem;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+/// The guessed letters of all boards of a round, coloured by whether they
+/// were right
+fn format_guesses(round: &Round, palette: &Palette) -> String {
+	let mut word_letters: HashSet<char> = HashSet::new();
+	let mut letters_guessed: HashSet<char> = HashSet::new();
+	let mut hinted_letters: HashSet<char> = HashSet::new();
+	for board in round.all_boards() {
+		word_letters.extend(board.word_letters());
+		letters_guessed.extend(&board.letters_guessed);
+		hinted_letters.extend(&board.hinted_letters);
+	}
+	let alphabet_letters = round.alphabet.language.letters().chars();
+	// letters outside of the alphabet can only be guessed if they're part of
+	// the word, they're listed after the others
+	let other_letters = letters_guessed
+		.iter()
+		.filter(|key| !round.alphabet.contains(**key))
+		.copied()
+		.sorted();
 
-fn format_guesses(word: &str, guessed: &HashSet<char>) -> String {
-	let letters = ('A'..='Z').filter_map(|c| {
-		let lower = c.clone().to_ascii_lowercase();
-		let lower_word = word.to_ascii_lowercase();
+	let letters = alphabet_letters.chain(other_letters).filter_map(|key| {
+		let letter = display_letter(key);
 
-		if guessed.contains(&lower) {
-			if lower_word.contains(&lower.to_string()) {
-				Some(c.to_string().dark_cyan().to_string())
+		if letters_guessed.contains(&key) {
+			if hinted_letters.contains(&key) {
+				Some(palette.hinted.apply(letter).to_string())
+			} else if word_letters.contains(&key) {
+				Some(palette.correct.apply(letter).to_string())
 			} else {
-				Some(c.to_string().dim().to_string())
+				Some(palette.missed.apply(letter).to_string())
 			}
 		} else {
 			None
@@ -30,17 +63,15 @@ fn format_guesses(word: &str, guessed: &HashSet<char>) -> String {
 	letters.collect()
 }
 
-pub fn format_word(word: &str, guessed: &HashSet<char>, insert_spaces: bool) -> String {
-	let chars = word.chars().map(|char| {
-		if !char.is_ascii_alphabetic() {
-			return char;
-		}
-		let lower = char.clone().to_ascii_lowercase();
-		if guessed.contains(&lower) {
-			char
-		} else {
-			'_'
-		}
+pub fn format_word(
+	word: &str,
+	guessed: &HashSet<char>,
+	alphabet: &Alphabet,
+	insert_spaces: bool,
+) -> String {
+	let chars = word.chars().map(|char| match alphabet.letter_key(char) {
+		Some(key) if !guessed.contains(&key) => '_',
+		_ => char,
 	});
 
 	if !insert_spaces {
@@ -52,153 +83,781 @@ pub fn format_word(word: &str, guessed: &HashSet<char>, insert_spaces: bool) ->
 }
 
 const GUESS_PROMPT: &str = "> ";
+const SOLVE_PROMPT: &str = "Solve: ";
+pub const SOLVE_KEY: char = '!';
+pub const HINT_KEY: char = '?';
 
 fn print_last_guess(last_guess: &char) {
-	println!("{}{}", GUESS_PROMPT, last_guess);
+	println!("{}{}", GUESS_PROMPT, display_letter(*last_guess));
 }
 
-fn format_word_and_guesses(word: &str, guessed: &HashSet<char>) {
+fn format_word_and_guesses(round: &Round, palette: &Palette) {
+	let Round {
+		word,
+		letters_guessed,
+		alphabet,
+		..
+	} = round;
 	println!();
-	println!("Phrase:      {}", format_word(word, guessed, true));
+	match (&round.category, round.difficulty) {
+		(Some(category), Some(difficulty)) => {
+			println!("Category:    {} ({})", category, difficulty)
+		}
+		(Some(category), None) => println!("Category:    {}", category),
+		(None, Some(difficulty)) => println!("Difficulty:  {}", difficulty),
+		(None, None) => (),
+	}
+	if let Some(clue) = &round.clue {
+		println!("Clue:        {}", clue);
+	}
+	println!(
+		"Phrase:      {}",
+		format_word(word, letters_guessed, alphabet, true)
+	);
 	println!();
-	println!("Guesses:     {}", format_guesses(word, guessed));
+	println!("Guesses:     {}", format_guesses(round, palette));
 	println!();
 }
 
-pub fn render_game(state: GameState) -> crossterm::Result<()> {
-	match &state.scene {
-		GameScene::GameEnd { feedback } => {
-			reset_screen()?;
-			match feedback {
-				EndFeedback::NoWordsFound => {
-					println!("Unfortunately there were no words matching your criteria :(");
+/// The gallows and how many wrong guesses and hints are left, followed by the
+/// phrase
+pub fn format_round(round: &Round, settings: &GameSettings, theme: &Theme) {
+	if !round.boards.is_empty() {
+		format_boards(round, settings, theme);
+		return;
+	}
+	let max_wrong_guesses = settings.max_wrong_guesses;
+	let wrong_guesses = round.wrong_guesses();
+	let guesses_left = usize::from(max_wrong_guesses).saturating_sub(wrong_guesses);
+	println!();
+	println!("{}", theme.stage(wrong_guesses, max_wrong_guesses));
+	println!();
+	let counter = format!("Wrong guesses left: {}", guesses_left);
+	if guesses_left == 0 {
+		println!("{}", theme.palette.bad.apply(counter));
+	} else {
+		println!("{}", counter);
+	}
+	if settings.max_hints > 0 {
+		let hints_left = usize::from(settings.max_hints).saturating_sub(round.hints_taken());
+		println!("Hints left:         {}", hints_left);
+	}
+	format_word_and_guesses(round, &theme.palette);
+}
+
+/// Space between the boards of a round
+const BOARD_GAP: usize = 4;
+
+/// A line of a board, styled once it's padded to the width of the board
+type BoardLine = (String, Option<ContentStyle>);
+
+/// The boards of a round in a grid, each with its own gallows and phrase. The
+/// grid is about as wide as it is high, unless the terminal is too narrow.
+fn format_boards(round: &Round, settings: &GameSettings, theme: &Theme) {
+	let max_wrong_guesses = settings.max_wrong_guesses;
+	let palette = &theme.palette;
+	let boards: Vec<Vec<BoardLine>> = round
+		.all_boards()
+		.enumerate()
+		.map(|(index, board)| {
+			let wrong_guesses = board.wrong_guesses();
+			let mut lines: Vec<BoardLine> = vec![(format!("Board {}", index + 1), None)];
+			lines.extend(
+				theme
+					.stage(wrong_guesses, max_wrong_guesses)
+					.lines()
+					.map(|line| (line.to_string(), None)),
+			);
+			lines.push(if board.is_solved() {
+				("Solved!".to_string(), Some(palette.good))
+			} else if board.is_finished(max_wrong_guesses) {
+				("Lost!".to_string(), Some(palette.bad))
+			} else {
+				let guesses_left = usize::from(max_wrong_guesses).saturating_sub(wrong_guesses);
+				(format!("Wrong guesses left: {}", guesses_left), None)
+			});
+			lines.push((String::new(), None));
+			lines.push((
+				format_word(&board.word, &board.letters_guessed, &board.alphabet, true),
+				None,
+			));
+			lines
+		})
+		.collect();
+
+	let width = boards
+		.iter()
+		.flatten()
+		.map(|(text, _)| text.chars().count())
+		.max()
+		.unwrap_or(0);
+	// some terminals don't tell their size
+	let terminal_width = match terminal::size() {
+		Ok((columns, _)) if columns > 0 => usize::from(columns),
+		_ => 80,
+	};
+	let columns = ((boards.len() as f64).sqrt().ceil() as usize)
+		.min((terminal_width + BOARD_GAP) / (width + BOARD_GAP))
+		.max(1);
+
+	for row in boards.chunks(columns) {
+		println!();
+		let height = row.iter().map(Vec::len).max().unwrap_or(0);
+		for index in 0..height {
+			let line = row
+				.iter()
+				.map(|board| {
+					let (text, style) = board.get(index).cloned().unwrap_or_default();
+					let padded = format!("{:<width$}", text, width = width);
+					match style {
+						Some(style) => style.apply(padded).to_string(),
+						None => padded,
+					}
+				})
+				.join(&" ".repeat(BOARD_GAP));
+			println!("{}", line.trim_end());
+		}
+	}
+
+	println!();
+	if settings.max_hints > 0 {
+		let hints_left = usize::from(settings.max_hints).saturating_sub(round.hints_taken());
+		println!("Hints left:  {}", hints_left);
+	}
+	for (index, board) in round.all_boards().enumerate() {
+		let about: Vec<String> = board
+			.category
+			.iter()
+			.cloned()
+			.chain(board.difficulty.map(|difficulty| difficulty.to_string()))
+			.chain(board.clue.iter().cloned())
+			.collect();
+		if !about.is_empty() {
+			println!("Board {}:     {}", index + 1, about.join(", "));
+		}
+	}
+	println!("Guesses:     {}", format_guesses(round, palette));
+	println!();
+}
+
+/// Every board of a finished round with its word, and whether it was solved
+fn format_boards_result(round: &Round, palette: &Palette) {
+	println!();
+	for (index, board) in round.all_boards().enumerate() {
+		let result = if board.is_solved() {
+			palette.good.apply("solved")
+		} else {
+			palette.bad.apply("lost")
+		};
+		println!("Board {}:     {}  ({})", index + 1, board.word, result);
+	}
+	println!();
+	println!("Guesses:     {}", format_guesses(round, palette));
+	println!();
+}
+
+/// Plays the game in the terminal the process runs in
+pub struct TerminalFrontend {
+	/// Used to record finished games in the high score table, and to save
+	/// unfinished ones
+	pub session: Session,
+	pub theme: Theme,
+	/// Set while a recorded game is played back, which only shows the scenes
+	/// and keeps no results
+	pub replay: bool,
+	/// Whether a scene was shown yet. The first one always shows the whole
+	/// round, e.g. of a resumed game whose last guess was only answered with
+	/// a line of text.
+	pub has_rendered: bool,
+}
+
+impl TerminalFrontend {
+	/// Adds the game to the high score table, in versus games once for every
+	/// player. Returns the players who got a new high score.
+	fn record_high_score(&self, state: &GameState) -> Result<Vec<String>, StorageError> {
+		let path = HighScores::default_path()?;
+		let mut high_scores = HighScores::load(&path)?;
+
+		let scores: Vec<(String, f64)> = if state.is_versus() {
+			state
+				.players
+				.iter()
+				.map(|player| (player.name.clone(), player.score))
+				.collect()
+		} else {
+			vec![(self.session.player.clone(), state.score)]
+		};
+		let mut new_high_scores = Vec::new();
+		for (player, score) in scores {
+			let is_high_score = high_scores.add(HighScoreEntry {
+				player: player.clone(),
+				mode: self.session.mode.clone(),
+				filter: self.session.filter.clone(),
+				max_wrong_guesses: state.settings.max_wrong_guesses,
+				scoring: state.settings.scoring.kind(),
+				score,
+				rounds: state.rounds_played,
+				date: game_date(state),
+			});
+			if is_high_score {
+				new_high_scores.push(player);
+			}
+		}
+
+		high_scores.save(&path)?;
+		Ok(new_high_scores)
+	}
+
+	fn is_daily(&self) -> bool {
+		self.session.mode == DAILY_MODE
+	}
+
+	/// How the players play together, in games joined over the network
+	fn net_mode(&self) -> Option<NetMode> {
+		self.session.mode.parse().ok()
+	}
+
+	/// Stores how the daily challenge of a day went
+	fn record_daily(&self, date: NaiveDate, outcome: DailyOutcome) -> Result<(), StorageError> {
+		let path = DailyResults::default_path()?;
+		let mut results = DailyResults::load(&path)?;
+		results.set(DailyResult {
+			date,
+			player: self.session.player.clone(),
+			outcome: Some(outcome),
+		});
+		results.save(&path)
+	}
+
+	/// Asks whether the game should be saved before quitting, and where
+	fn offer_save(&self, state: &GameState) -> crossterm::Result<()> {
+		println!("Save this game to continue later? [y]es / [n]o");
+		if !confirm_yn("> ")? {
+			return Ok(());
+		}
+
+		loop {
+			println!(
+				"Name of the save slot (leave empty for \"{}\"):",
+				DEFAULT_SLOT
+			);
+			let slot = get_word("> ", false, None)?;
+			println!();
+			let slot = match slot.trim() {
+				"" => DEFAULT_SLOT,
+				slot => slot,
+			};
+
+			let saved_game = SavedGame {
+				session: self.session.clone(),
+				state: state.clone(),
+				saved_at: Utc::now(),
+			};
+			match saved_game.save(slot) {
+				Ok(()) => {
+					let resume_command = match slot {
+						DEFAULT_SLOT => "hangman resume".to_string(),
+						slot => format!("hangman resume {}", slot),
+					};
+					println!("Saved! Continue any time with `{}`", resume_command);
+					println!("Press enter to quit");
+					return confirm_enter();
 				}
-				EndFeedback::NoMoreWordsFound => {
-					println!("Unfortunately we ran out of words matching your criteria! :(");
+				Err(StorageError::BadSlotName(slot)) => {
+					println!("{}", StorageError::BadSlotName(slot));
 				}
-				EndFeedback::ManuallyEnded => {
-					println!("Goodbye then! <3");
+				Err(error) => {
+					println!("The game could not be saved: {}", error);
+					println!("Press enter to quit");
+					return confirm_enter();
 				}
 			}
-			println!("Rounds played:  {}", state.rounds_played);
-			println!("Final score:    {:.0}", state.score);
-			Ok(())
 		}
-		GameScene::Init => {
-			reset_screen()?;
-			let is_first_game = state.rounds_played == 0;
-
-			match &state.args.mode {
-				GameMode::Manual(ManualGame { .. }) => {
-					if is_first_game {
-						println!("{}", "Manual mode".bold());
-					} else {
-						println!("Time for another round!");
+	}
+
+	/// Waits for the player to decide what happens in the current scene
+	pub fn read_action(&self, state: &GameState) -> crossterm::Result<GameAction> {
+		match &state.scene {
+			GameScene::Init => match state.settings.word_source {
+				WordSource::Manual => Ok(GameAction::StartManualGame(get_word("> ", true, None)?)),
+				WordSource::Random => {
+					confirm_enter()?;
+					Ok(GameAction::StartRandomGame)
+				}
+				WordSource::Reverse => unreachable!("reverse games have their own frontend"),
+			},
+			GameScene::AwaitingGuess { .. } => {
+				let deadline = state
+					.time_left()
+					.map(|time_left| Instant::now() + time_left);
+				let action = match get_char(GUESS_PROMPT, deadline) {
+					Ok(Some(SOLVE_KEY)) => {
+						get_word(SOLVE_PROMPT, false, deadline).map(GameAction::InputSolve)
 					}
-					println!();
-					println!("Enter your word or phrase:");
-					let word = get_word("> ")?;
-					render_game(state.start_manual_game(word))
-				}
-				GameMode::Random(RandomGame { .. }) => {
-					let unplayed_words = &state.unplayed_words;
-					if is_first_game {
-						println!("{}", "Random mode".bold());
-						println!(
-							"{} words and phrases matching your criteria were found",
-							unplayed_words.len()
-						);
-					} else {
-						println!("Time for another round!");
+					Ok(Some(HINT_KEY)) => Ok(GameAction::TakeHint),
+					Ok(guess) => Ok(GameAction::InputGuess(guess)),
+					Err(error) => Err(error),
+				};
+				match action {
+					Err(error) if is_timed_out(&error) => Ok(GameAction::TimeUp),
+					action => action,
+				}
+			}
+			GameScene::ValidGuess { .. } => Ok(GameAction::MakeGuess),
+			GameScene::ValidSolve { .. } => Ok(GameAction::MakeSolve),
+			GameScene::RoundEnd { .. } if self.is_daily() => {
+				confirm_enter()?;
+				Ok(GameAction::EndGame(EndFeedback::ManuallyEnded))
+			}
+			GameScene::RoundEnd { .. } => {
+				if confirm_yn("> ")? {
+					Ok(GameAction::NewRound)
+				} else {
+					Ok(GameAction::EndGame(EndFeedback::ManuallyEnded))
+				}
+			}
+			GameScene::GameEnd { .. } => unreachable!("the game is already over"),
+		}
+	}
+}
+
+impl Frontend for TerminalFrontend {
+	type Error = crossterm::ErrorKind;
+
+	fn render(&mut self, state: &GameState) -> crossterm::Result<()> {
+		let is_first_scene = !mem::replace(&mut self.has_rendered, true);
+		match &state.scene {
+			GameScene::GameEnd { feedback } => {
+				reset_screen()?;
+				match feedback {
+					EndFeedback::NoWordsFound => {
+						println!("Unfortunately there were no words matching your criteria :(");
+					}
+					EndFeedback::NoMoreWordsFound => {
+						println!("Unfortunately we ran out of words matching your criteria! :(");
+					}
+					EndFeedback::ManuallyEnded => {
+						println!("Goodbye then! <3");
 					}
+				}
+				println!("Rounds played:  {}", state.rounds_played);
+				if state.is_versus() {
 					println!();
-					println!("Press enter to start");
-					confirm_enter()?;
+					print_standings(state);
+				} else {
+					println!("Final score:    {:.0}", state.score);
+				}
+				if let Some(seed) = state.settings.seed {
+					println!("Seed:           {}", seed);
+					println!();
+					println!("To play the same words again, pass `--seed {}`", seed);
+					println!("together with the same word options.");
+				}
+				if !state.records.is_empty() {
+					println!();
+					print_round_records(&state.records);
+				}
 
-					render_game(state.start_random_game())
+				if state.rounds_played > 0 && !self.replay {
+					match self.record_high_score(state) {
+						Ok(players) => {
+							for player in players {
+								let message = if state.is_versus() {
+									format!("New high score for {}!", player)
+								} else {
+									"New high score!".to_string()
+								};
+								println!();
+								println!("{}", self.theme.palette.good.apply(message));
+							}
+						}
+						Err(error) => {
+							println!();
+							println!("Your score could not be saved: {}", error);
+						}
+					}
 				}
 			}
-		}
-		GameScene::AwaitingGuess {
-			letters_guessed,
-			feedback,
-			word,
-		} => {
-			match feedback {
+			GameScene::Init => {
+				reset_screen()?;
+				let is_first_game = state.rounds_played == 0;
+
+				match state.settings.word_source {
+					WordSource::Manual => {
+						if let (Some(setter), Some(guesser)) = (state.setter(), state.guesser()) {
+							println!("{}", format!("Round {}", state.rounds_played + 1).bold());
+							println!();
+							println!(
+								"{}, enter a word or phrase for {} to guess.",
+								setter.name, guesser.name
+							);
+							println!("{}, no peeking!", guesser.name);
+						} else {
+							if is_first_game {
+								println!("{}", "Manual mode".bold());
+							} else {
+								println!("Time for another round!");
+							}
+							println!();
+							println!("Enter your word or phrase:");
+						}
+					}
+					WordSource::Random if self.is_daily() => {
+						println!("{}", "Daily challenge".bold());
+						println!("Everybody gets the same word today, you only get one try!");
+						println!();
+						println!("Press enter to start");
+					}
+					WordSource::Random => {
+						match self.net_mode() {
+							// the words of networked games are only known to the host
+							Some(NetMode::Coop) if is_first_game => {
+								println!("{}", "Co-op mode".bold());
+								println!("Everybody guesses the same word together, any of you can start the round");
+							}
+							Some(NetMode::Race) if is_first_game => {
+								println!("{}", "Race mode".bold());
+								println!("Everybody guesses the same words on their own, who scores the most?");
+							}
+							None if is_first_game && state.settings.evil => {
+								println!("{}", "Evil mode".bold());
+								println!("The word keeps changing behind your back, as long as it fits what you found so far");
+							}
+							None if is_first_game => {
+								println!("{}", "Random mode".bold());
+								println!(
+									"{} words and phrases matching your criteria were found",
+									state.unplayed_words.len()
+								);
+							}
+							_ => println!("Time for another round!"),
+						}
+						println!();
+						println!("Press enter to start");
+					}
+					WordSource::Reverse => unreachable!("reverse games have their own frontend"),
+				}
+			}
+			GameScene::AwaitingGuess { round, feedback } => match feedback {
+				GuessFeedback::NoHintsLeft
+				| GuessFeedback::AlreadyTried(_)
+				| GuessFeedback::BadChar(_)
+				| GuessFeedback::BadSolve
+				| GuessFeedback::Contradiction
+					if is_first_scene =>
+				{
+					reset_screen()?;
+					format_round(round, &state.settings, &self.theme);
+					// the line of the feedback goes below the round, as usual
+					self.render(state)?;
+				}
 				GuessFeedback::LetsGo => {
 					reset_screen()?;
-					println!("Let's hang some men!");
-					format_word_and_guesses(word, letters_guessed);
+					match state.guesser() {
+						Some(guesser) => println!("{}, it's your turn to guess!", guesser.name),
+						None => println!("Let's hang some men!"),
+					}
+					println!(
+						"Guess a letter, or press {} to solve the whole phrase at once",
+						SOLVE_KEY
+					);
+					if let Some(time_limit) = state.settings.time_limit {
+						println!(
+							"You have {} seconds, the faster you are the more points you get",
+							time_limit
+						);
+					}
+					if state.settings.max_hints > 0 {
+						println!(
+							"Stuck? Press {} for a hint, it costs {} points",
+							HINT_KEY, state.settings.hint_penalty
+						);
+					}
+					format_round(round, &state.settings, &self.theme);
 				}
 				GuessFeedback::Correct(guess) => {
 					reset_screen()?;
 					print_last_guess(guess);
-					println!("{}", "Correct".dark_green().bold());
-					format_word_and_guesses(word, letters_guessed);
+					println!("{}", self.theme.palette.good.apply("Correct"));
+					format_round(round, &state.settings, &self.theme);
+				}
+				GuessFeedback::Hint(guess) => {
+					reset_screen()?;
+					println!("{}{}", GUESS_PROMPT, HINT_KEY);
+					println!(
+						"Hint: {}",
+						self.theme.palette.hinted.apply(display_letter(*guess))
+					);
+					format_round(round, &state.settings, &self.theme);
 				}
 				GuessFeedback::Wrong(guess) => {
 					reset_screen()?;
 					print_last_guess(guess);
-					println!("{}", "Wrong!".dark_red().bold());
-					format_word_and_guesses(word, letters_guessed);
+					println!("{}", self.theme.palette.bad.apply("Wrong!"));
+					format_round(round, &state.settings, &self.theme);
+				}
+				GuessFeedback::WrongSolve(phrase) => {
+					reset_screen()?;
+					println!("{}{}", SOLVE_PROMPT, phrase);
+					println!(
+						"{} That cost you {} guesses",
+						self.theme.palette.bad.apply("Wrong!"),
+						state.settings.solve_penalty
+					);
+					format_round(round, &state.settings, &self.theme);
+				}
+				GuessFeedback::NoHintsLeft => {
+					println!("You have no hints left!");
 				}
 				GuessFeedback::AlreadyTried(guess) => {
 					println!("You already tried '{}'!", guess);
 				}
 				GuessFeedback::BadChar(_) => {
-					println!("Please enter a letter (A - Z)")
+					let letters = state.settings.alphabet.language.letters();
+					println!(
+						"Please enter a letter ({}), or {} to solve the whole phrase",
+						letters.chars().map(display_letter).collect::<String>(),
+						SOLVE_KEY
+					)
+				}
+				GuessFeedback::BadSolve => {
+					println!("Please enter the whole word or phrase to solve it")
+				}
+				GuessFeedback::BoardSolved(phrase) => {
+					reset_screen()?;
+					println!("{}{}", SOLVE_PROMPT, phrase);
+					println!("{}", self.theme.palette.good.apply("Solved a board!"));
+					format_round(round, &state.settings, &self.theme);
+				}
+				GuessFeedback::Contradiction => {
+					println!("That doesn't fit the earlier answers");
+				}
+			},
+			GameScene::ValidGuess { guess, round } => {
+				for n in 0..=3 {
+					reset_screen()?;
+					if round.hinted_letters.contains(guess) {
+						println!("{}{}", GUESS_PROMPT, HINT_KEY);
+						print!("Revealing a letter");
+					} else {
+						print_last_guess(guess);
+						print!("Trying {}", display_letter(*guess));
+					}
+					print!("{}", ".".repeat(n));
+					stdout().flush().unwrap();
+					println!();
+					format_round(round, &state.settings, &self.theme);
+					thread::sleep(Duration::from_millis(100));
 				}
 			}
-
-			let guess = get_char(GUESS_PROMPT)?;
-			render_game(state.input_guess(guess))
-		}
-		GameScene::ValidGuess {
-			guess,
-			word,
-			letters_guessed,
-		} => {
-			for n in 0..=3 {
+			GameScene::ValidSolve { phrase, round } => {
+				for n in 0..=3 {
+					reset_screen()?;
+					println!("{}{}", SOLVE_PROMPT, phrase);
+					print!("Is it \"{}\"", phrase);
+					print!("{}", "?".repeat(n));
+					stdout().flush().unwrap();
+					println!();
+					format_round(round, &state.settings, &self.theme);
+					thread::sleep(Duration::from_millis(150));
+				}
+			}
+			GameScene::RoundEnd {
+				won,
+				round_score,
+				round,
+			} => {
+				let word = &round.word;
 				reset_screen()?;
-				print_last_guess(guess);
-				print!("Trying {}", guess);
-				print!("{}", ".".repeat(n));
-				stdout().flush().unwrap();
+				if !round.boards.is_empty() {
+					format_boards_result(round, &self.theme.palette);
+					println!();
+					if *won {
+						println!("{}", self.theme.won);
+						println!();
+						let multiplier = state
+							.settings
+							.scoring
+							.rule()
+							.bonuses()
+							.all_boards_solved_multiplier;
+						println!("All boards solved, that's {} times the score!", multiplier);
+					} else {
+						if round.timed_out {
+							println!("{}", self.theme.palette.bad.apply("Time's up!"));
+						}
+						println!("{}", self.theme.lost);
+					}
+				} else {
+					format_word_and_guesses(round, &self.theme.palette);
+					println!();
+					if *won {
+						println!("{}", self.theme.won);
+						println!();
+						println!("Phrase is:     {}", word);
+					} else {
+						if round.timed_out {
+							println!("{}", self.theme.palette.bad.apply("Time's up!"));
+						}
+						println!("{}", self.theme.lost);
+						println!();
+						println!("Phrase was:    {}", word);
+					}
+				}
+				println!();
+				println!("Round score:   {}", round_score);
+				if state.is_versus() {
+					println!();
+					print_standings(state);
+				} else {
+					println!("Total score:   {}", state.score);
+				}
 				println!();
-				format_word_and_guesses(word, letters_guessed);
-				thread::sleep(Duration::from_millis(100));
-			}
 
-			render_game(state.make_guess())
-		}
-		GameScene::RoundEnd {
-			word,
-			won,
-			round_score,
-			letters_guessed,
-		} => {
-			reset_screen()?;
-			format_word_and_guesses(word, letters_guessed);
-			println!();
-			if *won {
-				println!("{}", art::YOU_WON);
-				println!("Phrase is:     {}", word);
-			} else {
-				println!("{}", art::THE_HANGED_MAN);
-				println!("Phrase was:    {}", word);
+				if self.is_daily() {
+					let date = game_date(state);
+					let summary =
+						share_summary(date, round, *won, state.settings.max_wrong_guesses);
+					println!("{}", summary);
+					println!();
+					let outcome = DailyOutcome {
+						won: *won,
+						score: *round_score,
+						summary,
+					};
+					// replays show the result that was kept when the game was played
+					let kept = if self.replay {
+						Ok(())
+					} else {
+						self.record_daily(date, outcome)
+					};
+					if let Err(error) = kept {
+						println!("Your result could not be saved: {}", error);
+						println!();
+					}
+					println!("Come back tomorrow for a new word! Press enter to finish");
+				} else {
+					println!("Play another round? [y]es / [n]o");
+				}
 			}
-			println!();
-			println!("Round score:   {}", round_score);
-			println!("Total score:   {}", state.score);
-			println!();
-			println!("Play another round? [y]es / [n]o");
-			let yes = confirm_yn("> ")?;
+		}
+		Ok(())
+	}
 
-			if yes {
-				render_game(state.new_round())
-			} else {
-				render_game(state.end_game(EndFeedback::ManuallyEnded))
+	fn next_action(&mut self, state: &GameState) -> crossterm::Result<GameAction> {
+		match self.read_action(state) {
+			// somebody pressed Ctrl-C, there's something worth saving once the
+			// first round has started
+			Err(error) if is_interrupted(&error) && state.rounds_played > 0 => {
+				self.offer_save(state)?;
+				Err(error)
 			}
+			result => result,
 		}
 	}
 }
+
+/// The day of the daily challenge the game is, or today for any other game
+/// (and daily games saved before they knew their day)
+fn game_date(state: &GameState) -> NaiveDate {
+	state
+		.settings
+		.daily_date
+		.unwrap_or_else(|| Local::now().date_naive())
+}
+
+/// Prints the players of a versus game as a table, the best first
+fn print_standings(state: &GameState) {
+	println!(
+		"{}",
+		format!("{:>4}  {:<16} {:>7}", "#", "Player", "Score").bold()
+	);
+	for (place, player) in state.standings().iter().enumerate() {
+		println!(
+			"{:>4}  {:<16} {:>7.0}",
+			place + 1,
+			player.name,
+			player.score
+		);
+	}
+}
+
+/// Prints how every round went as a table, with the guesses in the order
+/// they were made. Hints are in parentheses, solve attempts in quotes.
+pub fn print_round_records(records: &[RoundRecord]) {
+	println!(
+		"{}",
+		format!(
+			"{:>4}  {:<24} {:<7} {:>7} {:>5} {:>6} {:>5} {:>6}  {}",
+			"#", "Word", "Result", "Score", "Hits", "Misses", "Hints", "Time", "Guesses"
+		)
+		.bold()
+	);
+	for (index, record) in records.iter().enumerate() {
+		let result = match (record.won, record.timed_out) {
+			(true, _) => "won",
+			(false, true) => "timeout",
+			(false, false) => "lost",
+		};
+		let seconds = record.duration().as_secs();
+		let guesses = record
+			.guesses
+			.iter()
+			.map(|guess| match &guess.guess {
+				Guess::Letter(letter) => display_letter(*letter),
+				Guess::Hint(letter) => format!("({})", display_letter(*letter)),
+				Guess::Solve(phrase) => format!("\"{}\"", phrase),
+			})
+			.join(" ");
+		println!(
+			"{:>4}  {:<24} {:<7} {:>7.0} {:>5} {:>6} {:>5} {:>3}:{:02}  {}",
+			index + 1,
+			record.words.join(", "),
+			result,
+			record.score,
+			record.hits(),
+			record.misses(),
+			record.hints,
+			seconds / 60,
+			seconds % 60,
+			guesses
+		);
+	}
+}
+
+/// Prints high score entries as a table, in the given order
+pub fn print_high_scores(entries: &[&HighScoreEntry]) {
+	if entries.is_empty() {
+		println!("No games were recorded yet, go play some!");
+		return;
+	}
+
+	println!(
+		"{}",
+		format!(
+			"{:>4}  {:<16} {:<8} {:<20} {:<8} {:>7} {:>7}  {}",
+			"#", "Player", "Mode", "Words", "Scoring", "Score", "Rounds", "Date"
+		)
+		.bold()
+	);
+	for (place, entry) in entries.iter().enumerate() {
+		let words = match &entry.filter {
+			Some(filter) => format!(
+				"{}-{} letters, {}-{}",
+				filter.min_length, filter.max_length, filter.min_score, filter.max_score
+			),
+			None => "-".to_string(),
+		};
+		println!(
+			"{:>4}  {:<16} {:<8} {:<20} {:<8} {:>7.0} {:>7}  {}",
+			place + 1,
+			entry.player,
+			entry.mode,
+			words,
+			entry.scoring,
+			entry.score,
+			entry.rounds,
+			entry.date
+		);
+	}
+}
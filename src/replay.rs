@@ -0,0 +1,111 @@
+use crate::art::Theme;
+use crate::input::{confirm_enter, poll_key};
+use crate::render::{TerminalFrontend, HINT_KEY, SOLVE_KEY};
+use crossterm::event::KeyCode;
+use crossterm::style::Stylize;
+use hangman::alphabet::display_letter;
+use hangman::driver::Frontend;
+use hangman::game_state::GameAction;
+use hangman::recording::{RecordedEvent, Recording};
+use std::time::{Duration, Instant};
+
+/// The longest wait between two events, so a player who went to make coffee
+/// doesn't stall the replay
+const MAX_GAP: Duration = Duration::from_secs(3);
+
+/// How much `+` and `-` change the speed
+const SPEED_STEP: f64 = 2.0;
+
+/// What the player typed for an action, `None` for the actions the game
+/// comes up with on its own
+fn typed(action: &GameAction) -> Option<String> {
+	match action {
+		// the word is a secret until the round is over
+		GameAction::StartManualGame(_) => Some("(a secret word)".to_string()),
+		GameAction::StartRandomGame => Some("(enter)".to_string()),
+		GameAction::InputGuess(Some(guess)) => Some(display_letter(*guess)),
+		GameAction::InputGuess(None) => Some("(not a letter)".to_string()),
+		GameAction::InputSolve(phrase) => Some(format!("{} {}", SOLVE_KEY, phrase)),
+		GameAction::TakeHint => Some(HINT_KEY.to_string()),
+		GameAction::TimeUp => Some("(time is up)".to_string()),
+		GameAction::NewRound => Some("y".to_string()),
+		GameAction::EndGame(_) => Some("n".to_string()),
+		_ => None,
+	}
+}
+
+/// Plays a recording back in the terminal, with the scenes drawn just like
+/// in the game. The player can pause, step through it event by event and
+/// change the speed.
+pub fn replay(recording: Recording, theme: Theme, speed: f64) -> crossterm::Result<()> {
+	let mut frontend = TerminalFrontend {
+		session: recording.header.session,
+		theme,
+		replay: true,
+		has_rendered: false,
+	};
+	let mut speed = speed;
+	let mut paused = false;
+
+	let mut events = recording.events.iter().peekable();
+	while let Some(event) = events.next() {
+		match event {
+			RecordedEvent::Scene { state, .. } => {
+				frontend.render(state)?;
+				println!();
+				println!(
+					"{}",
+					"Replay: [space] pause, [n] next step, [+/-] speed, [q] quit".dim()
+				);
+			}
+			RecordedEvent::Input { action, .. } => {
+				if let Some(typed) = typed(action) {
+					println!("{}", format!("> {}", typed).bold());
+				}
+			}
+		}
+
+		let next = match events.peek() {
+			Some(next) => next,
+			None => break,
+		};
+		let gap = Duration::from_millis(next.millis().saturating_sub(event.millis()));
+		let mut left = gap.div_f64(speed).min(MAX_GAP);
+		loop {
+			if !paused && left.is_zero() {
+				break;
+			}
+			let waiting = Instant::now();
+			// paused replays wait for a key as long as it takes
+			let timeout = if paused { MAX_GAP } else { left };
+			let key = poll_key(timeout)?;
+			left = left.saturating_sub(waiting.elapsed());
+			match key {
+				Some(KeyCode::Char(' ')) => {
+					paused = !paused;
+					println!("{}", if paused { "Paused" } else { "Playing" }.dim());
+				}
+				Some(KeyCode::Char('n')) | Some(KeyCode::Right) => {
+					paused = true;
+					break;
+				}
+				Some(KeyCode::Char('+')) => {
+					speed *= SPEED_STEP;
+					left = left.div_f64(SPEED_STEP);
+					println!("{}", format!("Speed: {}x", speed).dim());
+				}
+				Some(KeyCode::Char('-')) => {
+					speed /= SPEED_STEP;
+					left = left.mul_f64(SPEED_STEP).min(MAX_GAP);
+					println!("{}", format!("Speed: {}x", speed).dim());
+				}
+				Some(KeyCode::Char('q')) | Some(KeyCode::Esc) => return Ok(()),
+				_ => (),
+			}
+		}
+	}
+
+	println!();
+	println!("That's the end of the recording. Press enter to quit");
+	confirm_enter()
+}
@@ -0,0 +1,207 @@
+use crate::art::Theme;
+use crate::input::{confirm_yn, get_word};
+use crate::render::{format_round, print_round_records};
+use crate::reset_screen;
+use crossterm::style::Stylize;
+use hangman::alphabet::display_letter;
+use hangman::driver::Frontend;
+use hangman::game_state::{EndFeedback, GameAction, GameScene, GameState, GuessFeedback};
+use hangman::solver::{Move, Solver};
+
+/// How many of the words that still fit are shown when the computer gives up
+const SHOWN_CANDIDATES: usize = 5;
+
+/// Plays reverse games in the terminal: the player thinks of a word and
+/// answers the guesses of a [Solver]
+pub struct ReverseFrontend {
+	pub solver: Solver,
+	pub theme: Theme,
+	/// The move the solver made last, to explain it while the player answers
+	last_move: Option<Move>,
+}
+
+impl ReverseFrontend {
+	pub fn new(solver: Solver, theme: Theme) -> Self {
+		ReverseFrontend {
+			solver,
+			theme,
+			last_move: None,
+		}
+	}
+
+	fn print_reason(&self) {
+		if let Some(last_move) = &self.last_move {
+			println!("{}", format!("({})", last_move.reason()).dim());
+		}
+	}
+}
+
+/// The lengths of the words of a phrase, e.g. "5 3". `None` unless they're
+/// all numbers above 0.
+fn parse_lengths(answer: &str) -> Option<Vec<usize>> {
+	let lengths: Option<Vec<usize>> = answer
+		.split(|c: char| c.is_whitespace() || c == ',')
+		.filter(|part| !part.is_empty())
+		.map(|part| part.parse().ok().filter(|length| *length > 0))
+		.collect();
+	lengths.filter(|lengths| !lengths.is_empty())
+}
+
+/// The places of a letter, counted from 1 by the player and from 0 by the
+/// game. Nothing at all means the letter isn't part of the phrase.
+fn parse_places(answer: &str) -> Option<Vec<usize>> {
+	answer
+		.split(|c: char| c.is_whitespace() || c == ',')
+		.filter(|part| !part.is_empty())
+		.map(|part| part.parse::<usize>().ok()?.checked_sub(1))
+		.collect()
+}
+
+impl Frontend for ReverseFrontend {
+	type Error = crossterm::ErrorKind;
+
+	fn render(&mut self, state: &GameState) -> crossterm::Result<()> {
+		let palette = &self.theme.palette;
+		match &state.scene {
+			GameScene::Init => {
+				reset_screen()?;
+				if state.rounds_played == 0 {
+					println!("{}", "Reverse mode".bold());
+					println!("Think of a word or phrase and I'll try to guess it. Don't tell me what it is!");
+				} else {
+					println!("Time for another round!");
+				}
+				println!();
+				println!("How many letters does it have? For a phrase, give the letters of every word, e.g. 5 3");
+			}
+			GameScene::AwaitingGuess { round, feedback } => {
+				reset_screen()?;
+				match feedback {
+					GuessFeedback::LetsGo => println!("Let's see..."),
+					GuessFeedback::Correct(guess) => {
+						println!(
+							"{}",
+							palette
+								.good
+								.apply(format!("{} it is!", display_letter(*guess)))
+						)
+					}
+					GuessFeedback::Wrong(guess) => {
+						println!(
+							"{}",
+							palette
+								.bad
+								.apply(format!("No {}, hmm...", display_letter(*guess)))
+						)
+					}
+					GuessFeedback::WrongSolve(phrase) => println!("Not \"{}\" then", phrase),
+					GuessFeedback::Contradiction => println!(
+						"{}",
+						palette
+							.bad
+							.apply("That doesn't fit what you told me before, let's try again")
+					),
+					_ => (),
+				}
+				format_round(round, &state.settings, &self.theme);
+			}
+			GameScene::ValidGuess { round, guess } => {
+				reset_screen()?;
+				format_round(round, &state.settings, &self.theme);
+				println!("Does it have the letter {}?", display_letter(*guess).bold());
+				self.print_reason();
+				println!();
+				println!("Enter the places it is at, counting the letters from 1, e.g. 2 5");
+				println!("Just press enter if it's not in there");
+			}
+			GameScene::ValidSolve { round, phrase } => {
+				reset_screen()?;
+				format_round(round, &state.settings, &self.theme);
+				println!("Is it \"{}\"?", phrase.as_str().bold());
+				self.print_reason();
+				println!();
+				println!("[y]es / [n]o");
+			}
+			GameScene::RoundEnd { round, won, .. } => {
+				reset_screen()?;
+				if *won {
+					println!("{}", palette.good.apply("I got it!"));
+					println!();
+					println!("It's:          {}", round.word);
+				} else {
+					println!("{}", palette.bad.apply("You got me, I give up!"));
+					println!();
+					let candidates = self.solver.candidates(round);
+					if candidates.is_empty() {
+						println!("I don't know any word that fits {}", round.word);
+					} else {
+						println!("I thought it was one of these:");
+						for candidate in candidates.iter().take(SHOWN_CANDIDATES) {
+							println!("  {}", candidate);
+						}
+						if candidates.len() > SHOWN_CANDIDATES {
+							println!("  and {} more", candidates.len() - SHOWN_CANDIDATES);
+						}
+					}
+				}
+				println!();
+				println!("My score:      {}", state.score);
+				println!();
+				println!("Play another round? [y]es / [n]o");
+			}
+			GameScene::GameEnd { .. } => {
+				reset_screen()?;
+				println!("Goodbye then! <3");
+				println!("Rounds played:  {}", state.rounds_played);
+				println!("My score:       {:.0}", state.score);
+				if !state.records.is_empty() {
+					println!();
+					print_round_records(&state.records);
+				}
+			}
+		}
+		Ok(())
+	}
+
+	fn next_action(&mut self, state: &GameState) -> crossterm::Result<GameAction> {
+		match &state.scene {
+			GameScene::Init => loop {
+				let answer = get_word("> ", false, None)?;
+				println!();
+				match parse_lengths(&answer) {
+					Some(lengths) => return Ok(GameAction::StartReverseGame(lengths)),
+					None => {
+						println!("Please enter the number of letters, e.g. 7, or 5 3 for a phrase")
+					}
+				}
+			},
+			GameScene::AwaitingGuess { round, .. } => {
+				self.last_move = self.solver.next_move(round);
+				match &self.last_move {
+					Some(next) => Ok(next.action()),
+					// every letter was tried, all that's left is giving up
+					None => Ok(GameAction::GiveUp),
+				}
+			}
+			GameScene::ValidGuess { .. } => loop {
+				let answer = get_word("> ", false, None)?;
+				println!();
+				match parse_places(&answer) {
+					Some(places) => return Ok(GameAction::AnswerGuess(places)),
+					None => {
+						println!("Please enter the places as numbers from 1, or nothing at all")
+					}
+				}
+			},
+			GameScene::ValidSolve { .. } => Ok(GameAction::AnswerSolve(confirm_yn("> ")?)),
+			GameScene::RoundEnd { .. } => {
+				if confirm_yn("> ")? {
+					Ok(GameAction::NewRound)
+				} else {
+					Ok(GameAction::EndGame(EndFeedback::ManuallyEnded))
+				}
+			}
+			GameScene::GameEnd { .. } => unreachable!("the game is already over"),
+		}
+	}
+}
@@ -0,0 +1,77 @@
+use crate::errors::StorageError;
+use crate::game_state::GameState;
+use crate::storage::{data_dir, read_json, write_json};
+use crate::word_list::WordFilter;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+/// The slot games are saved to when no other one is picked
+pub const DEFAULT_SLOT: &str = "default";
+
+/// Who is playing which mode, everything about a game that is not part of
+/// its [GameState]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+	pub player: String,
+	/// The game mode, e.g. "random"
+	pub mode: String,
+	/// Which words could come up, `None` in modes where players enter the words
+	pub filter: Option<WordFilter>,
+}
+
+/// An unfinished game that can be picked up again later
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SavedGame {
+	pub session: Session,
+	pub state: GameState,
+	/// When the game was saved. The clock of a timed round stops until it's
+	/// resumed.
+	#[serde(default = "Utc::now")]
+	pub saved_at: DateTime<Utc>,
+}
+
+impl SavedGame {
+	/// Where a slot is kept. Slot names may only contain letters, digits, `-`
+	/// and `_`, so they can't point anywhere else.
+	pub fn path(slot: &str) -> Result<PathBuf, StorageError> {
+		let is_valid = !slot.is_empty()
+			&& slot
+				.chars()
+				.all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+		if !is_valid {
+			return Err(StorageError::BadSlotName(slot.to_string()));
+		}
+
+		Ok(data_dir()?.join("saves").join(format!("{}.json", slot)))
+	}
+
+	/// Saves the game, replacing whatever was in the slot before
+	pub fn save(&self, slot: &str) -> Result<(), StorageError> {
+		write_json(&SavedGame::path(slot)?, self)
+	}
+
+	/// Takes the game out of its slot, so it can't be resumed twice. `None` if
+	/// nothing was saved in the slot. The round goes on with the time that
+	/// was left when it was saved.
+	pub fn take(slot: &str) -> Result<Option<SavedGame>, StorageError> {
+		let path = SavedGame::path(slot)?;
+		let mut saved: Option<SavedGame> = read_json(&path)?;
+		if let Some(saved) = &mut saved {
+			let pause = (Utc::now() - saved.saved_at).to_std().unwrap_or_default();
+			saved.state.resume_after(pause);
+		}
+
+		if saved.is_some() {
+			match fs::remove_file(&path) {
+				Err(error) if error.kind() != ErrorKind::NotFound => {
+					return Err(StorageError::io(&path, error));
+				}
+				_ => (),
+			}
+		}
+		Ok(saved)
+	}
+}
@@ -0,0 +1,374 @@
+use crate::errors::StorageError;
+use crate::game_state::Round;
+use crate::storage::{data_dir, read_json};
+use crate::word_list::Difficulty;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Decides what a round is worth
+pub trait ScoringRule {
+	/// The score of a won round, before the bonus for being fast and the
+	/// penalty for hints are applied
+	fn won_score(&self, round: &Round, max_wrong_guesses: u8) -> f64;
+
+	/// The score of a lost round
+	fn lost_score(&self, _round: &Round, _max_wrong_guesses: u8) -> f64 {
+		0.0
+	}
+
+	/// What the rule adds on top in some games
+	fn bonuses(&self) -> &Bonuses;
+}
+
+/// The parts of a score that only some games have, every rule has its own
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Bonuses {
+	/// The points for every letter that was still hidden when the phrase was
+	/// solved at once
+	pub solve_hidden_letter_bonus: f64,
+	/// What the player who entered the word gets in versus games, when the
+	/// other one doesn't guess it
+	pub stumped_setter_score: f64,
+	/// How much the score of a won round grows in timed games when it's won
+	/// right away, it shrinks the longer the round takes. 1 doubles it.
+	pub speed_bonus: f64,
+	/// What the scores of the boards are multiplied with when all of them are
+	/// solved
+	pub all_boards_solved_multiplier: f64,
+}
+
+impl Default for Bonuses {
+	fn default() -> Self {
+		Bonuses {
+			solve_hidden_letter_bonus: 10.0,
+			stumped_setter_score: 50.0,
+			speed_bonus: 1.0,
+			all_boards_solved_multiplier: 2.0,
+		}
+	}
+}
+
+/// How many more wrong guesses the round could have taken
+fn left_guesses(round: &Round, max_wrong_guesses: u8) -> i32 {
+	i32::from(max_wrong_guesses) - i32::try_from(round.wrong_guesses()).unwrap()
+}
+
+/// The score grows exponentially with every wrong guess that is left
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Classic {
+	pub base_score: f64,
+	pub left_guess_multiplier: f64,
+	#[serde(flatten)]
+	pub bonuses: Bonuses,
+}
+
+impl Default for Classic {
+	fn default() -> Self {
+		Classic {
+			base_score: 10.0,
+			left_guess_multiplier: 1.75,
+			bonuses: Bonuses::default(),
+		}
+	}
+}
+
+impl ScoringRule for Classic {
+	fn won_score(&self, round: &Round, max_wrong_guesses: u8) -> f64 {
+		let left_guesses = left_guesses(round, max_wrong_guesses);
+		self.base_score * self.left_guess_multiplier.powi(left_guesses)
+	}
+
+	fn bonuses(&self) -> &Bonuses {
+		&self.bonuses
+	}
+}
+
+/// Every letter of the word is worth points like in Scrabble, rare letters
+/// more than common ones
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Rarity {
+	/// The points of each letter key (see [crate::alphabet::Alphabet::letter_key]).
+	/// The letters that are left out keep their Scrabble points.
+	#[serde(deserialize_with = "over_scrabble_scores")]
+	pub letter_scores: HashMap<char, f64>,
+	/// The points of letters that are missing from `letter_scores`
+	pub other_letter_score: f64,
+	/// The points for every wrong guess that is left
+	pub left_guess_score: f64,
+	#[serde(flatten)]
+	pub bonuses: Bonuses,
+}
+
+/// The points of the english letters in Scrabble
+fn scrabble_scores() -> HashMap<char, f64> {
+	let scrabble = [
+		("aeilnorstu", 1.0),
+		("dg", 2.0),
+		("bcmp", 3.0),
+		("fhvwy", 4.0),
+		("k", 5.0),
+		("jx", 8.0),
+		("qz", 10.0),
+	];
+	scrabble
+		.iter()
+		.flat_map(|(letters, score)| letters.chars().map(move |letter| (letter, *score)))
+		.collect()
+}
+
+/// Reads letter scores that replace some of the [scrabble_scores]
+fn over_scrabble_scores<'de, D: Deserializer<'de>>(
+	deserializer: D,
+) -> Result<HashMap<char, f64>, D::Error> {
+	let mut letter_scores = scrabble_scores();
+	letter_scores.extend(HashMap::<char, f64>::deserialize(deserializer)?);
+	Ok(letter_scores)
+}
+
+impl Default for Rarity {
+	fn default() -> Self {
+		Rarity {
+			letter_scores: scrabble_scores(),
+			other_letter_score: 5.0,
+			left_guess_score: 5.0,
+			bonuses: Bonuses::default(),
+		}
+	}
+}
+
+impl ScoringRule for Rarity {
+	fn won_score(&self, round: &Round, max_wrong_guesses: u8) -> f64 {
+		let letters: f64 = round
+			.alphabet
+			.letter_keys(&round.word)
+			.chars()
+			.map(|key| {
+				self.letter_scores
+					.get(&key)
+					.copied()
+					.unwrap_or(self.other_letter_score)
+			})
+			.sum();
+		letters + f64::from(left_guesses(round, max_wrong_guesses)) * self.left_guess_score
+	}
+
+	fn bonuses(&self) -> &Bonuses {
+		&self.bonuses
+	}
+}
+
+/// Long and hard words are worth more, and lost rounds still score the
+/// letters that were found
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Length {
+	pub letter_score: f64,
+	pub left_guess_multiplier: f64,
+	/// What the score of words the word list calls easy, medium or hard is
+	/// multiplied with
+	pub easy_multiplier: f64,
+	pub medium_multiplier: f64,
+	pub hard_multiplier: f64,
+	/// The points for every letter that was found in a lost round
+	pub found_letter_score: f64,
+	#[serde(flatten)]
+	pub bonuses: Bonuses,
+}
+
+impl Default for Length {
+	fn default() -> Self {
+		Length {
+			letter_score: 5.0,
+			left_guess_multiplier: 1.25,
+			easy_multiplier: 1.0,
+			medium_multiplier: 1.5,
+			hard_multiplier: 2.0,
+			found_letter_score: 1.0,
+			bonuses: Bonuses::default(),
+		}
+	}
+}
+
+impl ScoringRule for Length {
+	fn won_score(&self, round: &Round, max_wrong_guesses: u8) -> f64 {
+		let letters = round.alphabet.letter_keys(&round.word).chars().count() as f64;
+		let difficulty_multiplier = match round.difficulty {
+			Some(Difficulty::Easy) | None => self.easy_multiplier,
+			Some(Difficulty::Medium) => self.medium_multiplier,
+			Some(Difficulty::Hard) => self.hard_multiplier,
+		};
+		let left_guesses = left_guesses(round, max_wrong_guesses);
+		letters
+			* self.letter_score
+			* difficulty_multiplier
+			* self.left_guess_multiplier.powi(left_guesses)
+	}
+
+	fn lost_score(&self, round: &Round, _max_wrong_guesses: u8) -> f64 {
+		let found = round
+			.word
+			.chars()
+			.filter(|c| round.alphabet.letter_key(*c).is_some() && !round.is_hidden(*c))
+			.count();
+		found as f64 * self.found_letter_score
+	}
+
+	fn bonuses(&self) -> &Bonuses {
+		&self.bonuses
+	}
+}
+
+/// Every round starts with the same score, and every wrong guess costs
+/// points, in lost rounds as well
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Penalty {
+	pub base_score: f64,
+	pub wrong_guess_penalty: f64,
+	#[serde(flatten)]
+	pub bonuses: Bonuses,
+}
+
+impl Default for Penalty {
+	fn default() -> Self {
+		Penalty {
+			base_score: 100.0,
+			wrong_guess_penalty: 10.0,
+			bonuses: Bonuses::default(),
+		}
+	}
+}
+
+impl ScoringRule for Penalty {
+	fn won_score(&self, round: &Round, _max_wrong_guesses: u8) -> f64 {
+		(self.base_score - round.wrong_guesses() as f64 * self.wrong_guess_penalty).max(0.0)
+	}
+
+	fn lost_score(&self, round: &Round, _max_wrong_guesses: u8) -> f64 {
+		-(round.wrong_guesses() as f64 * self.wrong_guess_penalty)
+	}
+
+	fn bonuses(&self) -> &Bonuses {
+		&self.bonuses
+	}
+}
+
+/// The built-in scoring rules, as they're picked on the command line
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScoringKind {
+	#[default]
+	Classic,
+	Rarity,
+	Length,
+	Penalty,
+}
+
+crate::named_enum!(ScoringKind, "scoring rule", {
+	Classic => "classic",
+	Rarity => "rarity",
+	Length => "length",
+	Penalty => "penalty",
+});
+
+/// The scoring rule a game is played with, and its parameters
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "rule", rename_all = "lowercase")]
+pub enum Scoring {
+	Classic(Classic),
+	Rarity(Rarity),
+	Length(Length),
+	Penalty(Penalty),
+}
+
+impl Default for Scoring {
+	fn default() -> Self {
+		Scoring::Classic(Classic::default())
+	}
+}
+
+impl Scoring {
+	pub fn kind(&self) -> ScoringKind {
+		match self {
+			Scoring::Classic(_) => ScoringKind::Classic,
+			Scoring::Rarity(_) => ScoringKind::Rarity,
+			Scoring::Length(_) => ScoringKind::Length,
+			Scoring::Penalty(_) => ScoringKind::Penalty,
+		}
+	}
+
+	pub fn rule(&self) -> &dyn ScoringRule {
+		match self {
+			Scoring::Classic(rule) => rule,
+			Scoring::Rarity(rule) => rule,
+			Scoring::Length(rule) => rule,
+			Scoring::Penalty(rule) => rule,
+		}
+	}
+}
+
+/// The parameters of every scoring rule, as they can be tuned in
+/// `scoring.json`. Everything that's left out keeps its default.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScoringConfig {
+	pub classic: Classic,
+	pub rarity: Rarity,
+	pub length: Length,
+	pub penalty: Penalty,
+}
+
+impl ScoringConfig {
+	pub fn default_path() -> Result<PathBuf, StorageError> {
+		Ok(data_dir()?.join("scoring.json"))
+	}
+
+	/// Loads the parameters, the defaults if there is no config file
+	pub fn load(path: &Path) -> Result<ScoringConfig, StorageError> {
+		Ok(read_json(path)?.unwrap_or_default())
+	}
+
+	/// The rule of that kind, with the parameters of the config
+	pub fn scoring(&self, kind: ScoringKind) -> Scoring {
+		match kind {
+			ScoringKind::Classic => Scoring::Classic(self.classic.clone()),
+			ScoringKind::Rarity => Scoring::Rarity(self.rarity.clone()),
+			ScoringKind::Length => Scoring::Length(self.length.clone()),
+			ScoringKind::Penalty => Scoring::Penalty(self.penalty.clone()),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn left_out_parameters_keep_their_defaults() {
+		let config: ScoringConfig = serde_json::from_str(
+			r#"{ "rarity": { "letter_scores": { "q": 12 }, "stumped_setter_score": 20 } }"#,
+		)
+		.unwrap();
+
+		assert_eq!(config.rarity.letter_scores[&'q'], 12.0);
+		assert_eq!(config.rarity.letter_scores[&'z'], 10.0);
+		assert_eq!(config.rarity.bonuses.stumped_setter_score, 20.0);
+		assert_eq!(config.rarity.bonuses.speed_bonus, 1.0);
+		assert_eq!(config.classic, Classic::default());
+	}
+
+	#[test]
+	fn saved_rules_keep_their_bonuses() {
+		let mut rule = Penalty::default();
+		rule.bonuses.all_boards_solved_multiplier = 3.0;
+		let saved = serde_json::to_string(&Scoring::Penalty(rule)).unwrap();
+
+		let scoring: Scoring = serde_json::from_str(&saved).unwrap();
+		assert_eq!(scoring.rule().bonuses().all_boards_solved_multiplier, 3.0);
+		assert_eq!(scoring.rule().bonuses().solve_hidden_letter_bonus, 10.0);
+	}
+}
@@ -0,0 +1,399 @@
+use crate::alphabet::{display_letter, Alphabet};
+use crate::game_state::{GameAction, Guess, Round, MASK_CHAR};
+use crate::word_list::WordListEntry;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Display, Formatter};
+
+/// How the solver picks its next guess
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Strategy {
+	/// The letter that is part of the most words of the whole list
+	Frequency,
+	/// The letter that is part of the most words that still fit the round
+	Candidates,
+	/// The letter that tells the most about which of the words that still fit
+	/// it is, by splitting them into the most even groups
+	Entropy,
+}
+
+crate::named_enum!(Strategy, "strategy", {
+	Frequency => "frequency",
+	Candidates => "candidates",
+	Entropy => "entropy",
+});
+
+/// Why the solver made a move
+#[derive(Debug, Clone, PartialEq)]
+pub enum Reason {
+	/// The letter is part of `words` of the `total` words of the list
+	ListFrequency {
+		letter: char,
+		words: usize,
+		total: usize,
+	},
+	/// The letter is part of `words` of the `candidates` that still fit
+	CandidateFrequency {
+		letter: char,
+		words: usize,
+		candidates: usize,
+	},
+	/// Guessing the letter splits the `candidates` into `groups` by where it
+	/// shows up, which is worth `bits` of information on average
+	Entropy {
+		letter: char,
+		groups: usize,
+		bits: f64,
+		candidates: usize,
+	},
+	/// The phrase is the only word of the list that still fits
+	OnlyCandidate,
+	/// No word of the list fits anymore, so the most common letter of the
+	/// list it is
+	NoCandidates { letter: char },
+}
+
+impl Reason {
+	/// The letter the reason is about, `None` when solving
+	pub fn letter(&self) -> Option<char> {
+		match self {
+			Reason::ListFrequency { letter, .. }
+			| Reason::CandidateFrequency { letter, .. }
+			| Reason::Entropy { letter, .. }
+			| Reason::NoCandidates { letter } => Some(*letter),
+			Reason::OnlyCandidate => None,
+		}
+	}
+}
+
+impl Display for Reason {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			Reason::ListFrequency {
+				letter,
+				words,
+				total,
+			} => write!(
+				f,
+				"{} is in {} of the {} words of the list",
+				display_letter(*letter),
+				words,
+				total
+			),
+			Reason::CandidateFrequency {
+				letter,
+				words,
+				candidates,
+			} => write!(
+				f,
+				"{} is in {} of the {} words that still fit",
+				display_letter(*letter),
+				words,
+				candidates
+			),
+			Reason::Entropy {
+				letter,
+				groups,
+				bits,
+				candidates,
+			} => write!(
+				f,
+				"{} splits the {} words that still fit into {} groups, worth {:.2} bits",
+				display_letter(*letter),
+				candidates,
+				groups,
+				bits
+			),
+			Reason::OnlyCandidate => write!(f, "it's the only word that still fits"),
+			Reason::NoCandidates { letter } => write!(
+				f,
+				"no word of the list fits anymore, {} is the most common letter",
+				display_letter(*letter)
+			),
+		}
+	}
+}
+
+/// What the solver wants to do next
+#[derive(Debug, Clone, PartialEq)]
+pub enum Move {
+	Guess { letter: char, reason: Reason },
+	Solve { phrase: String, reason: Reason },
+}
+
+impl Move {
+	pub fn reason(&self) -> &Reason {
+		match self {
+			Move::Guess { reason, .. } | Move::Solve { reason, .. } => reason,
+		}
+	}
+
+	/// The action that makes the move in a round
+	pub fn action(&self) -> GameAction {
+		match self {
+			Move::Guess { letter, .. } => GameAction::InputGuess(Some(*letter)),
+			Move::Solve { phrase, .. } => GameAction::InputSolve(phrase.clone()),
+		}
+	}
+}
+
+/// A phrase of the list, with its letter keys (see [Alphabet::letter_key])
+#[derive(Debug)]
+struct Word {
+	phrase: String,
+	keys: Vec<char>,
+	/// How many letters each of its words has, see [word_lengths]
+	lengths: Vec<usize>,
+}
+
+/// How many letters each word of a phrase has, e.g. `[4, 3]` for "T-Pain
+/// too". `is_letter` tells the letters apart from everything else.
+fn word_lengths(phrase: &str, is_letter: impl Fn(char) -> bool) -> Vec<usize> {
+	phrase
+		.split_whitespace()
+		.map(|word| word.chars().filter(|c| is_letter(*c)).count())
+		.filter(|length| *length > 0)
+		.collect()
+}
+
+/// Plays rounds on its own, from a list of the words that could come up.
+///
+/// It only looks at what players can see of a round, the masked word and the
+/// letters that were guessed, never at the word itself.
+#[derive(Debug)]
+pub struct Solver {
+	strategy: Strategy,
+	alphabet: Alphabet,
+	words: Vec<Word>,
+	/// How many words of the list each letter key is part of
+	list_counts: HashMap<char, usize>,
+}
+
+impl Solver {
+	pub fn new(strategy: Strategy, word_list: &[WordListEntry], alphabet: Alphabet) -> Solver {
+		let mut seen = HashSet::new();
+		let words: Vec<Word> = word_list
+			.iter()
+			.map(|entry| Word {
+				phrase: entry.phrase.clone(),
+				keys: alphabet.letter_keys(&entry.phrase).chars().collect(),
+				lengths: word_lengths(&entry.phrase, |c| alphabet.letter_key(c).is_some()),
+			})
+			// phrases that only differ in case or punctuation can't be told
+			// apart
+			.filter(|word| seen.insert((word.keys.clone(), word.lengths.clone())))
+			.collect();
+		let list_counts = count_letters(words.iter());
+
+		Solver {
+			strategy,
+			alphabet,
+			words,
+			list_counts,
+		}
+	}
+
+	pub fn strategy(&self) -> Strategy {
+		self.strategy
+	}
+
+	/// The words of the list that fit what is known about the round: its
+	/// words have the same lengths, the revealed letters are in their places,
+	/// and none of the hidden ones were guessed. Phrases that were tried to
+	/// solve the round are left out.
+	pub fn candidates(&self, round: &Round) -> Vec<&str> {
+		self.candidate_words(round)
+			.into_iter()
+			.map(|word| word.phrase.as_str())
+			.collect()
+	}
+
+	fn candidate_words(&self, round: &Round) -> Vec<&Word> {
+		let masked_word = round.masked_word();
+		let lengths = word_lengths(&masked_word, |c| {
+			c == MASK_CHAR || self.alphabet.letter_key(c).is_some()
+		});
+		// `None` for the letters that are still hidden
+		let pattern: Vec<Option<char>> = masked_word
+			.chars()
+			.filter_map(|c| match c {
+				MASK_CHAR => Some(None),
+				c => self.alphabet.letter_key(c).map(Some),
+			})
+			.collect();
+		let tried: Vec<String> = round
+			.history
+			.iter()
+			.filter_map(|guess| match guess {
+				Guess::Solve(phrase) => Some(self.alphabet.letter_keys(phrase)),
+				_ => None,
+			})
+			.collect();
+
+		self.words
+			.iter()
+			.filter(|word| {
+				word.lengths == lengths
+					&& word
+						.keys
+						.iter()
+						.zip(&pattern)
+						.all(|(key, known)| match known {
+							Some(known) => key == known,
+							None => !round.letters_guessed.contains(key),
+						}) && !tried.contains(&word.keys.iter().collect())
+			})
+			.collect()
+	}
+
+	/// Decides on the next move for the round, `None` if there's nothing left
+	/// to try
+	pub fn next_move(&self, round: &Round) -> Option<Move> {
+		let guessable: Vec<char> = round
+			.alphabet
+			.language
+			.letters()
+			.chars()
+			.filter(|key| !round.letters_guessed.contains(key))
+			.collect();
+		let candidates = self.candidate_words(round);
+		let most_common_in_list = || best_letter(&guessable, |key| count(&self.list_counts, key));
+
+		let reason = match self.strategy {
+			Strategy::Frequency => {
+				let letter = most_common_in_list()?;
+				Reason::ListFrequency {
+					letter,
+					words: count(&self.list_counts, letter),
+					total: self.words.len(),
+				}
+			}
+			_ if candidates.is_empty() => Reason::NoCandidates {
+				letter: most_common_in_list()?,
+			},
+			_ if candidates.len() == 1 => {
+				return Some(Move::Solve {
+					phrase: candidates[0].phrase.clone(),
+					reason: Reason::OnlyCandidate,
+				})
+			}
+			Strategy::Candidates => {
+				let counts = count_letters(candidates.iter().copied());
+				let letter = best_letter(&guessable, |key| count(&counts, key))?;
+				Reason::CandidateFrequency {
+					letter,
+					words: count(&counts, letter),
+					candidates: candidates.len(),
+				}
+			}
+			Strategy::Entropy => {
+				let counts = count_letters(candidates.iter().copied());
+				let splits: HashMap<char, (usize, f64)> = guessable
+					.iter()
+					.map(|key| (*key, split(&candidates, *key)))
+					.collect();
+				// the most information first, then the most common letter
+				let letter = best_letter(&guessable, |key| {
+					((splits[&key].1 * 1e6) as u64, count(&counts, key))
+				})?;
+				let (groups, bits) = splits[&letter];
+				Reason::Entropy {
+					letter,
+					groups,
+					bits,
+					candidates: candidates.len(),
+				}
+			}
+		};
+
+		let letter = reason.letter()?;
+		Some(Move::Guess { letter, reason })
+	}
+}
+
+/// How many words each letter key is part of
+fn count_letters<'a>(words: impl Iterator<Item = &'a Word>) -> HashMap<char, usize> {
+	let mut counts = HashMap::new();
+	for word in words {
+		let keys: HashSet<&char> = word.keys.iter().collect();
+		for key in keys {
+			*counts.entry(*key).or_insert(0) += 1;
+		}
+	}
+	counts
+}
+
+fn count(counts: &HashMap<char, usize>, key: char) -> usize {
+	counts.get(&key).copied().unwrap_or(0)
+}
+
+/// The letter with the highest value, the earlier letter in the alphabet
+/// wins a tie. `None` if there are no letters.
+fn best_letter<T: Ord>(letters: &[char], value: impl Fn(char) -> T) -> Option<char> {
+	letters
+		.iter()
+		.copied()
+		.max_by_key(|letter| (value(*letter), Reverse(*letter)))
+}
+
+/// Groups the words by the places the letter shows up in, and returns the
+/// number of groups and their entropy in bits
+fn split(words: &[&Word], key: char) -> (usize, f64) {
+	let mut groups: HashMap<Vec<usize>, usize> = HashMap::new();
+	for word in words {
+		let places = word
+			.keys
+			.iter()
+			.enumerate()
+			.filter(|(_, other)| **other == key)
+			.map(|(place, _)| place)
+			.collect();
+		*groups.entry(places).or_insert(0) += 1;
+	}
+
+	let total = words.len() as f64;
+	let bits = groups
+		.values()
+		.map(|size| {
+			let share = *size as f64 / total;
+			-share * share.log2()
+		})
+		.sum();
+	(groups.len(), bits)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn solver(phrases: &[&str]) -> Solver {
+		let word_list: Vec<WordListEntry> = phrases
+			.iter()
+			.map(|phrase| WordListEntry::new(phrase, 50))
+			.collect();
+		Solver::new(Strategy::Entropy, &word_list, Alphabet::default())
+	}
+
+	#[test]
+	fn candidates_have_the_same_words() {
+		let solver = solver(&["ab cd", "abc d", "abcd", "Ab-cd"]);
+
+		let round = Round::new("ab cd".to_string(), Alphabet::default());
+		assert_eq!(solver.candidates(&round), ["ab cd"]);
+
+		let round = Round::new("abcd".to_string(), Alphabet::default());
+		assert_eq!(solver.candidates(&round), ["abcd"]);
+	}
+
+	#[test]
+	fn candidates_fit_the_revealed_letters() {
+		let solver = solver(&["cat", "cot", "dog", "cart"]);
+		let mut round = Round::new("cat".to_string(), Alphabet::default());
+		round.letters_guessed.insert('t');
+		round.letters_guessed.insert('o');
+
+		assert_eq!(solver.candidates(&round), ["cat"]);
+	}
+}
@@ -0,0 +1,37 @@
+use crate::errors::StorageError;
+use serde::{de::DeserializeOwned, Serialize};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// The directory the game keeps its files in, e.g. `~/.local/share/hangman`
+/// (or wherever `$XDG_DATA_HOME` points to)
+pub fn data_dir() -> Result<PathBuf, StorageError> {
+	dirs::data_dir()
+		.map(|dir| dir.join("hangman"))
+		.ok_or(StorageError::NoDataDir)
+}
+
+/// Reads a JSON file, a file that doesn't exist yet is `None`
+pub fn read_json<T: DeserializeOwned>(path: &Path) -> Result<Option<T>, StorageError> {
+	let contents = match fs::read_to_string(path) {
+		Ok(contents) => contents,
+		Err(error) if error.kind() == ErrorKind::NotFound => return Ok(None),
+		Err(error) => return Err(StorageError::io(path, error)),
+	};
+
+	serde_json::from_str(&contents)
+		.map(Some)
+		.map_err(|error| StorageError::json(path, error))
+}
+
+/// Writes a JSON file, creating its directory if needed
+pub fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), StorageError> {
+	if let Some(dir) = path.parent() {
+		fs::create_dir_all(dir).map_err(|error| StorageError::io(dir, error))?;
+	}
+
+	let contents =
+		serde_json::to_string_pretty(value).map_err(|error| StorageError::json(path, error))?;
+	fs::write(path, contents).map_err(|error| StorageError::io(path, error))
+}
@@ -1,21 +1,204 @@
-use clap::Clap;
+use crate::art::DEFAULT_THEME;
+use crate::protocol::Protocol;
+use clap::{Clap, Error as ClapError, ErrorKind as ClapErrorKind};
+use std::env;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::path::PathBuf;
 
-use crate::errors::ValidationError;
+use hangman::alphabet::Language;
+use hangman::daily::DAILY_MODE;
+use hangman::game_state::{HintKind, MAX_BOARDS};
+use hangman::high_scores::HighScoreEntry;
+use hangman::net::NetMode;
+use hangman::saves::DEFAULT_SLOT;
+use hangman::scoring::ScoringKind;
+use hangman::solver::Strategy;
+use hangman::word_list::WordFilter;
 
 pub trait Validatable {
 	fn validate(&self) -> Result<(), ValidationError>;
 }
 
+#[derive(Debug, Clone)]
+pub enum ValidationError {
+	/// Occurs in random mode, when the passed parameters did not match any
+	/// entries.
+	NoWordsFound,
+
+	/// Occurs in random mode, when the passed parameters did not match any
+	/// entries other than the ones already played.
+	NoMoreWordsFound,
+
+	/// Occurs in random mode, when a passed min length is higher than max length
+	MinLengthIsBiggerThanMaxLength { min_length: u8, max_length: u8 },
+
+	/// Occurs in random mode, when a passed min score is higher than max score
+	MinScoreIsBiggerThanMaxScore { min_score: u8, max_score: u8 },
+
+	/// Occurs in random mode, when hints would add to the score
+	NegativeHintPenalty(f64),
+
+	/// Occurs when a time limit of zero seconds is passed
+	ZeroTimeLimit,
+
+	/// Occurs in versus mode, when two players have the same name
+	DuplicatePlayer(String),
+
+	/// Occurs in random mode, when the number of boards is out of range
+	BadBoardCount(u8),
+
+	/// Occurs when several boards are asked for in a mode that only plays one
+	BoardsNotSupported(&'static str),
+
+	/// Occurs when `--record` is passed in a mode that can't be recorded
+	RecordingNotSupported(&'static str),
+
+	/// Occurs when `--scoring` is passed in a mode whose games are always
+	/// scored the same way, like the daily challenge
+	ScoringNotSupported(&'static str),
+
+	/// Occurs in replay mode, when the speed is not above zero
+	BadReplaySpeed(f64),
+}
+
+impl Error for ValidationError {}
+
+impl From<ValidationError> for ClapError {
+	fn from(e: ValidationError) -> Self {
+		match e {
+			ValidationError::NoWordsFound => ClapError::with_description(
+				"Could not find any words using the given parameters".to_string(),
+				ClapErrorKind::ValueValidation,
+			),
+			ValidationError::NoMoreWordsFound => ClapError::with_description(
+				"Could not find any more words using the given parameters".to_string(),
+				ClapErrorKind::ValueValidation,
+			),
+			ValidationError::MinLengthIsBiggerThanMaxLength {
+				min_length,
+				max_length,
+			} => ClapError::with_description(
+				format!(
+					"Min word length ({}) must be smaller than max length ({})",
+					min_length, max_length
+				),
+				ClapErrorKind::ValueValidation,
+			),
+			ValidationError::MinScoreIsBiggerThanMaxScore {
+				min_score,
+				max_score,
+			} => ClapError::with_description(
+				format!(
+					"Min word score ({}) must be smaller than max score ({})",
+					min_score, max_score
+				),
+				ClapErrorKind::ValueValidation,
+			),
+			ValidationError::NegativeHintPenalty(hint_penalty) => ClapError::with_description(
+				format!("Hint penalty ({}) must not be negative", hint_penalty),
+				ClapErrorKind::ValueValidation,
+			),
+			ValidationError::ZeroTimeLimit => ClapError::with_description(
+				"Time limit must be at least one second".to_string(),
+				ClapErrorKind::ValueValidation,
+			),
+			ValidationError::DuplicatePlayer(name) => ClapError::with_description(
+				format!("There are two players called \"{}\"", name),
+				ClapErrorKind::ValueValidation,
+			),
+			ValidationError::BadBoardCount(boards) => ClapError::with_description(
+				format!(
+					"Number of boards ({}) must be between 1 and {}",
+					boards, MAX_BOARDS
+				),
+				ClapErrorKind::ValueValidation,
+			),
+			ValidationError::BoardsNotSupported(mode) => ClapError::with_description(
+				format!("Only a single board can be played in {} mode", mode),
+				ClapErrorKind::ValueValidation,
+			),
+			ValidationError::RecordingNotSupported(mode) => ClapError::with_description(
+				format!("Games of {} mode can't be recorded", mode),
+				ClapErrorKind::ValueValidation,
+			),
+			ValidationError::ScoringNotSupported(mode) => ClapError::with_description(
+				format!("The scoring rule can't be picked in {} mode", mode),
+				ClapErrorKind::ValueValidation,
+			),
+			ValidationError::BadReplaySpeed(speed) => ClapError::with_description(
+				format!("Replay speed ({}) must be above 0", speed),
+				ClapErrorKind::ValueValidation,
+			),
+		}
+	}
+}
+
+impl Display for ValidationError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		let as_clap: ClapError = self.to_owned().into();
+		as_clap.fmt(f)
+	}
+}
+
 #[derive(Clap, Debug)]
 #[clap(name = "hangman", author = "remmycat")]
 pub struct HangmanCliOptions {
+	/// Your name for the high score table, defaults to your user name
+	#[clap(short = 'n', long)]
+	pub name: Option<String>,
+
+	/// How the game looks, one of the built-in themes (classic, snowman,
+	/// minimal) or the name of a file in ~/.local/share/hangman/themes
+	/// without its ".theme" extension
+	#[clap(short = 't', long, default_value = DEFAULT_THEME)]
+	pub theme: String,
+
+	/// How to play: in the terminal, or with JSON lines on stdin and stdout
+	/// for scripts and bots (terminal, jsonl)
+	#[clap(long, default_value = "terminal")]
+	pub protocol: Protocol,
+
+	/// How rounds are scored (classic, rarity, length, penalty), the
+	/// parameters of every rule can be tuned in
+	/// ~/.local/share/hangman/scoring.json
+	#[clap(long, default_value = "classic")]
+	pub scoring: ScoringKind,
+
+	/// Write everything that happens in the game to this file, to watch it
+	/// again with `hangman replay`
+	#[clap(long, value_name = "FILE")]
+	pub record: Option<PathBuf>,
+
 	#[clap(subcommand)]
 	pub mode: GameMode,
 }
 impl Validatable for HangmanCliOptions {
 	fn validate(&self) -> Result<(), ValidationError> {
-		let HangmanCliOptions { mode } = self;
-		mode.validate()
+		let HangmanCliOptions {
+			mode,
+			record,
+			scoring,
+			..
+		} = self;
+		mode.validate()?;
+		if record.is_some() && !mode.can_be_recorded() {
+			return Err(ValidationError::RecordingNotSupported(mode.name()));
+		}
+		if *scoring != ScoringKind::default() && !mode.can_pick_scoring() {
+			return Err(ValidationError::ScoringNotSupported(mode.name()));
+		}
+		Ok(())
+	}
+}
+
+impl HangmanCliOptions {
+	pub fn player_name(&self) -> String {
+		self.name
+			.clone()
+			.or_else(|| env::var("USER").ok())
+			.or_else(|| env::var("USERNAME").ok())
+			.unwrap_or_else(|| "Anonymous".to_string())
 	}
 }
 
@@ -30,13 +213,107 @@ pub enum GameMode {
 	/// Manual mode allows you to enter a word in secret, that another person
 	/// can then guess.
 	Manual(ManualGame),
+	/// Versus mode is manual mode for two or more players, who take turns
+	/// entering a word for the next one to guess. Everybody keeps their own
+	/// score.
+	Versus(VersusGame),
+	/// Evil mode is random mode with a game that cheats. It doesn't settle on
+	/// a word, but keeps switching to whichever words that fit everything
+	/// you found so far are the most, so every guess hurts.
+	Evil(RandomGame),
+	/// Daily mode gives everybody the same word from the built-in list each
+	/// day. It can be played once per day, and ends with a summary of your
+	/// guesses to share with others.
+	Daily(DailyGame),
+	/// Host mode starts a random game that others can join over the network
+	/// with `hangman join`. Everybody either guesses the same word together
+	/// (coop), or the same words on their own (race).
+	Host(HostedGame),
+	/// Joins a game that somebody started with `hangman host`
+	Join(JoinGame),
+	/// Lets the computer play random mode and explain every guess. Over many
+	/// rounds, it shows how hard the words are.
+	Solve(SolveGame),
+	/// Reverse mode turns the tables: you think of a word or phrase, and the
+	/// computer guesses it from the word list. You tell it where each letter
+	/// it guesses is.
+	Reverse(ReverseGame),
+	/// Continues a game that was saved when quitting with Ctrl-C
+	Resume(ResumeGame),
+	/// Plays back a game that was recorded with `--record`
+	Replay(ReplayGame),
+	/// Shows the best games that were played on this machine
+	Scores(ShowScores),
 }
 
 impl Validatable for GameMode {
 	fn validate(&self) -> Result<(), ValidationError> {
 		match self {
 			GameMode::Manual(manual) => manual.validate(),
+			GameMode::Versus(versus) => versus.validate(),
 			GameMode::Random(random) => random.validate(),
+			GameMode::Evil(evil) => {
+				evil.validate()?;
+				evil.single_board("evil")
+			}
+			GameMode::Daily(daily) => daily.validate(),
+			GameMode::Host(host) => host.validate(),
+			GameMode::Join(join) => join.validate(),
+			GameMode::Solve(solve) => solve.validate(),
+			GameMode::Reverse(reverse) => reverse.validate(),
+			GameMode::Resume(resume) => resume.validate(),
+			GameMode::Replay(replay) => replay.validate(),
+			GameMode::Scores(scores) => scores.validate(),
+		}
+	}
+}
+
+impl GameMode {
+	/// Whether games of the mode can be recorded with `--record`, only those
+	/// that are played with a single frontend on this machine can
+	pub fn can_be_recorded(&self) -> bool {
+		matches!(
+			self,
+			GameMode::Random(_)
+				| GameMode::Manual(_)
+				| GameMode::Versus(_)
+				| GameMode::Evil(_)
+				| GameMode::Daily(_)
+				| GameMode::Resume(_)
+		)
+	}
+
+	/// Whether `--scoring` picks the scoring rule of the mode's games. Daily
+	/// challenges are always scored the classic way, so everybody's results
+	/// can be compared, resumed games keep the rule they were started with
+	/// and joined ones play by the host's.
+	pub fn can_pick_scoring(&self) -> bool {
+		matches!(
+			self,
+			GameMode::Random(_)
+				| GameMode::Manual(_)
+				| GameMode::Versus(_)
+				| GameMode::Evil(_)
+				| GameMode::Host(_)
+				| GameMode::Reverse(_)
+		)
+	}
+
+	/// How the mode is called on the command line and in the high score table
+	pub fn name(&self) -> &'static str {
+		match self {
+			GameMode::Random(_) => "random",
+			GameMode::Manual(_) => "manual",
+			GameMode::Versus(_) => "versus",
+			GameMode::Evil(_) => "evil",
+			GameMode::Daily(_) => DAILY_MODE,
+			GameMode::Host(_) => "host",
+			GameMode::Join(_) => "join",
+			GameMode::Solve(_) => "solve",
+			GameMode::Reverse(_) => "reverse",
+			GameMode::Resume(_) => "resume",
+			GameMode::Replay(_) => "replay",
+			GameMode::Scores(_) => "scores",
 		}
 	}
 }
@@ -62,6 +339,89 @@ pub struct RandomGame {
 	/// TODO make better
 	#[clap(short = 'W', long, default_value = "6")]
 	pub max_wrong_guesses: u8,
+
+	/// How many wrong guesses a failed attempt to solve the whole phrase costs
+	#[clap(short = 'P', long, default_value = "2")]
+	pub solve_penalty: u8,
+
+	/// The alphabet letters are guessed from (english, german, spanish, greek,
+	/// cyrillic)
+	#[clap(short = 'a', long, default_value = "english")]
+	pub alphabet: Language,
+
+	/// Count letters with accents as the same guess as the letter without them,
+	/// e.g. guessing "e" also reveals "é"
+	#[clap(short = 'f', long)]
+	pub fold_accents: bool,
+
+	/// A file to pick words from instead of the built-in list, can be passed
+	/// multiple times. Each line is of the form "phrase::score", lines starting
+	/// with "# " are comments.
+	#[clap(
+		short = 'w',
+		long = "word-list",
+		value_name = "PATH",
+		multiple_occurrences = true,
+		number_of_values = 1
+	)]
+	pub word_lists: Vec<PathBuf>,
+
+	/// Also use the built-in list when passing word lists
+	#[clap(short = 'b', long)]
+	pub with_builtin: bool,
+
+	/// Shuffle the words with this seed, to play the same words as somebody
+	/// else. The seed of every game is shown at the end.
+	#[clap(long)]
+	pub seed: Option<u64>,
+
+	/// Only play words of this category, for word lists that have categories
+	#[clap(short = 'c', long)]
+	pub category: Option<String>,
+
+	/// How many hints you can take per round, by pressing ?
+	#[clap(short = 'H', long, default_value = "3")]
+	pub max_hints: u8,
+
+	/// Which letter a hint reveals, a random hidden one or the most common
+	/// hidden one (random, common)
+	#[clap(long, default_value = "random")]
+	pub hint_kind: HintKind,
+
+	/// How much score each hint takes off a won round
+	#[clap(long, default_value = "10")]
+	pub hint_penalty: f64,
+
+	/// Seconds you have to guess each word, the faster you are the higher
+	/// your score. Running out of time loses the round.
+	#[clap(short = 'T', long, value_name = "SECS")]
+	pub time_limit: Option<u32>,
+
+	/// How many words to guess at once. Every guess counts for all of them,
+	/// but each has its own wrong guesses. Solving all of them multiplies the
+	/// score, by 2 unless the scoring rule says otherwise.
+	#[clap(long, default_value = "1")]
+	pub boards: u8,
+}
+
+impl RandomGame {
+	pub fn word_filter(&self) -> WordFilter {
+		WordFilter {
+			min_length: self.min_length,
+			max_length: self.max_length,
+			min_score: self.min_score,
+			max_score: self.max_score,
+			category: self.category.clone(),
+		}
+	}
+
+	/// Rejects more than one board, for modes that can't show several
+	pub fn single_board(&self, mode: &'static str) -> Result<(), ValidationError> {
+		match self.boards {
+			1 => Ok(()),
+			_ => Err(ValidationError::BoardsNotSupported(mode)),
+		}
+	}
 }
 
 impl Validatable for RandomGame {
@@ -83,6 +443,16 @@ impl Validatable for RandomGame {
 				min_score: *min_score,
 				max_score: *max_score,
 			}),
+			RandomGame { hint_penalty, .. } if *hint_penalty < 0.0 => {
+				Err(ValidationError::NegativeHintPenalty(*hint_penalty))
+			}
+			RandomGame {
+				time_limit: Some(0),
+				..
+			} => Err(ValidationError::ZeroTimeLimit),
+			RandomGame { boards, .. } if *boards == 0 || *boards > MAX_BOARDS => {
+				Err(ValidationError::BadBoardCount(*boards))
+			}
 			_ => Ok(()),
 		}
 	}
@@ -93,9 +463,242 @@ pub struct ManualGame {
 	/// TODO make better
 	#[clap(short = 'W', long, default_value = "6")]
 	pub max_wrong_guesses: u8,
+
+	/// How many wrong guesses a failed attempt to solve the whole phrase costs
+	#[clap(short = 'P', long, default_value = "2")]
+	pub solve_penalty: u8,
+
+	/// The alphabet letters are guessed from (english, german, spanish, greek,
+	/// cyrillic)
+	#[clap(short = 'a', long, default_value = "english")]
+	pub alphabet: Language,
+
+	/// Count letters with accents as the same guess as the letter without them,
+	/// e.g. guessing "e" also reveals "é"
+	#[clap(short = 'f', long)]
+	pub fold_accents: bool,
+
+	/// Seconds you have to guess each word, the faster you are the higher
+	/// your score. Running out of time loses the round.
+	#[clap(short = 'T', long, value_name = "SECS")]
+	pub time_limit: Option<u32>,
 }
 impl Validatable for ManualGame {
+	fn validate(&self) -> Result<(), ValidationError> {
+		match self.time_limit {
+			Some(0) => Err(ValidationError::ZeroTimeLimit),
+			_ => Ok(()),
+		}
+	}
+}
+
+#[derive(Clap, Debug)]
+pub struct VersusGame {
+	#[clap(flatten)]
+	pub rules: ManualGame,
+
+	/// The names of everybody who plays, in the order they take turns guessing
+	#[clap(required = true, min_values = 2)]
+	pub players: Vec<String>,
+}
+impl Validatable for VersusGame {
+	fn validate(&self) -> Result<(), ValidationError> {
+		self.rules.validate()?;
+		match self
+			.players
+			.iter()
+			.enumerate()
+			.find(|(index, name)| self.players[..*index].contains(name))
+		{
+			Some((_, name)) => Err(ValidationError::DuplicatePlayer(name.clone())),
+			None => Ok(()),
+		}
+	}
+}
+
+#[derive(Clap, Debug)]
+pub struct DailyGame {}
+impl Validatable for DailyGame {
 	fn validate(&self) -> Result<(), ValidationError> {
 		Ok(())
 	}
 }
+
+#[derive(Clap, Debug)]
+pub struct HostedGame {
+	/// The port players connect to
+	#[clap(short = 'p', long, default_value = "7878")]
+	pub port: u16,
+
+	/// How everybody plays: guessing the same word together (coop), or the
+	/// same words on their own, each with their own score (race)
+	#[clap(short = 'm', long, default_value = "coop")]
+	pub mode: NetMode,
+
+	#[clap(flatten)]
+	pub rules: RandomGame,
+}
+impl Validatable for HostedGame {
+	fn validate(&self) -> Result<(), ValidationError> {
+		self.rules.validate()?;
+		self.rules.single_board("host")
+	}
+}
+
+#[derive(Clap, Debug)]
+pub struct JoinGame {
+	/// The address of the host, e.g. 192.168.1.20 or localhost:7878. Without
+	/// a port, 7878 is used.
+	pub address: String,
+}
+impl Validatable for JoinGame {
+	fn validate(&self) -> Result<(), ValidationError> {
+		Ok(())
+	}
+}
+
+#[derive(Clap, Debug)]
+pub struct SolveGame {
+	/// How the computer picks letters: the most common letter of the word
+	/// list (frequency), the most common letter of the words that still fit
+	/// (candidates), or the letter that tells the most about them (entropy)
+	#[clap(long, default_value = "entropy")]
+	pub strategy: Strategy,
+
+	/// How many rounds the computer plays
+	#[clap(short = 'r', long, default_value = "1")]
+	pub rounds: u32,
+
+	/// Only show how the computer did, not every guess
+	#[clap(short = 'q', long)]
+	pub quiet: bool,
+
+	#[clap(flatten)]
+	pub rules: RandomGame,
+}
+impl Validatable for SolveGame {
+	fn validate(&self) -> Result<(), ValidationError> {
+		self.rules.validate()?;
+		self.rules.single_board("solve")
+	}
+}
+
+#[derive(Clap, Debug)]
+pub struct ReverseGame {
+	/// How the computer picks letters, see `hangman solve --help`
+	#[clap(long, default_value = "entropy")]
+	pub strategy: Strategy,
+
+	#[clap(flatten)]
+	pub rules: RandomGame,
+}
+impl Validatable for ReverseGame {
+	fn validate(&self) -> Result<(), ValidationError> {
+		self.rules.validate()?;
+		self.rules.single_board("reverse")
+	}
+}
+
+#[derive(Clap, Debug)]
+pub struct ReplayGame {
+	/// The file the game was recorded to
+	pub file: PathBuf,
+
+	/// How much faster than the recorded game to play it back
+	#[clap(short = 's', long, default_value = "1")]
+	pub speed: f64,
+}
+impl Validatable for ReplayGame {
+	fn validate(&self) -> Result<(), ValidationError> {
+		if self.speed.is_nan() || self.speed <= 0.0 {
+			return Err(ValidationError::BadReplaySpeed(self.speed));
+		}
+		Ok(())
+	}
+}
+
+#[derive(Clap, Debug)]
+pub struct ResumeGame {
+	/// The slot the game was saved to
+	#[clap(default_value = DEFAULT_SLOT)]
+	pub slot: String,
+}
+impl Validatable for ResumeGame {
+	fn validate(&self) -> Result<(), ValidationError> {
+		Ok(())
+	}
+}
+
+#[derive(Clap, Debug)]
+pub struct ShowScores {
+	/// Only show games of this mode (random, manual, versus, evil, daily,
+	/// coop, race)
+	#[clap(short = 'm', long)]
+	pub mode: Option<String>,
+
+	/// Only show games of this player
+	#[clap(short = 'p', long)]
+	pub player: Option<String>,
+
+	/// Only show games with this minimum number of letters per word
+	#[clap(short = 'l', long)]
+	pub min_length: Option<u8>,
+
+	/// Only show games with this maximum number of letters per word
+	#[clap(short = 'L', long)]
+	pub max_length: Option<u8>,
+
+	/// Only show games with this minimum "word coolness score"
+	#[clap(short = 's', long)]
+	pub min_score: Option<u8>,
+
+	/// Only show games with this maximum "word coolness score"
+	#[clap(short = 'S', long)]
+	pub max_score: Option<u8>,
+
+	/// Only show games that were played with words of this category
+	#[clap(long)]
+	pub category: Option<String>,
+
+	/// Only show games with this number of allowed wrong guesses
+	#[clap(short = 'W', long)]
+	pub max_wrong_guesses: Option<u8>,
+
+	/// Only show games that were scored with this rule (classic, rarity,
+	/// length, penalty)
+	#[clap(long)]
+	pub scoring: Option<ScoringKind>,
+
+	/// How many games to show
+	#[clap(short = 'c', long, default_value = "10")]
+	pub count: usize,
+}
+
+impl Validatable for ShowScores {
+	fn validate(&self) -> Result<(), ValidationError> {
+		Ok(())
+	}
+}
+
+impl ShowScores {
+	/// Whether an entry has all the settings that were asked for
+	pub fn matches(&self, entry: &HighScoreEntry) -> bool {
+		fn matches_filter<T: PartialEq>(wanted: &Option<T>, actual: Option<T>) -> bool {
+			match wanted {
+				Some(wanted) => actual.as_ref() == Some(wanted),
+				None => true,
+			}
+		}
+		let filter = entry.filter.as_ref();
+
+		matches_filter(&self.mode, Some(entry.mode.clone()))
+			&& matches_filter(&self.player, Some(entry.player.clone()))
+			&& matches_filter(&self.min_length, filter.map(|f| f.min_length))
+			&& matches_filter(&self.max_length, filter.map(|f| f.max_length))
+			&& matches_filter(&self.min_score, filter.map(|f| f.min_score))
+			&& matches_filter(&self.max_score, filter.map(|f| f.max_score))
+			&& matches_filter(&self.category, filter.and_then(|f| f.category.clone()))
+			&& matches_filter(&self.max_wrong_guesses, Some(entry.max_wrong_guesses))
+			&& matches_filter(&self.scoring, Some(entry.scoring))
+	}
+}
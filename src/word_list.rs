@@ -1,39 +1,203 @@
-use rand::seq::SliceRandom;
+use crate::alphabet::Alphabet;
+use crate::errors::WordListError;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
 
 static GIANT_CROSSWORD_WORD_LIST: &str = include_str!("../assets/crossword-phrases.txt");
+static GIANT_CROSSWORD_WORD_LIST_NAME: &str = "built-in word list";
 
-pub fn get_filtered_word_list(
-	min_length: u8,
-	max_length: u8,
-	min_score: u8,
-	max_score: u8,
-) -> Vec<&'static str> {
-	let filtered: Vec<&str> = GIANT_CROSSWORD_WORD_LIST
-		.lines()
-		.filter(|line| !line.starts_with("# "))
-		.filter_map(|line| {
-			// 1. Parse line of form phrase::score into those values
+/// How hard a word is to guess, according to whoever wrote the word list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+	Easy,
+	Medium,
+	Hard,
+}
+
+crate::named_enum!(Difficulty, "difficulty", {
+	Easy => "easy",
+	Medium => "medium",
+	Hard => "hard",
+});
+
+/// A single line of a word list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WordListEntry {
+	pub phrase: String,
+	/// "word coolness score" (0-100)
+	pub score: u8,
+	/// What kind of thing the phrase is, e.g. "City"
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub category: Option<String>,
+	/// Something that helps guessing the phrase, like a crossword clue
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub clue: Option<String>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub difficulty: Option<Difficulty>,
+}
+
+impl WordListEntry {
+	/// An entry without a category, clue or difficulty
+	pub fn new(phrase: &str, score: u8) -> WordListEntry {
+		WordListEntry {
+			phrase: phrase.to_string(),
+			score,
+			category: None,
+			clue: None,
+			difficulty: None,
+		}
+	}
+
+	/// Whether the entry is of that category, ignoring case
+	pub fn is_in_category(&self, category: &str) -> bool {
+		match &self.category {
+			Some(own) => own.eq_ignore_ascii_case(category.trim()),
+			None => false,
+		}
+	}
+}
 
+/// Which words of a list should come up in a game
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WordFilter {
+	/// Counting letters only
+	pub min_length: u8,
+	pub max_length: u8,
+	pub min_score: u8,
+	pub max_score: u8,
+	/// Only words of this category, see [WordListEntry::category]
+	#[serde(default)]
+	pub category: Option<String>,
+}
+
+/// Parses a word list, made of lines of the form `phrase::score`. They can
+/// be followed by a category, a clue and a difficulty (easy, medium or hard),
+/// as in `phrase::score::category::clue::difficulty`. Each of those can be
+/// left empty or out. Lines starting with `# ` are comments, empty lines are
+/// ignored.
+///
+/// `source` is the name the list is referred to by in errors, e.g. its path.
+pub fn parse_word_list(source: &str, contents: &str) -> Result<Vec<WordListEntry>, WordListError> {
+	contents
+		.lines()
+		.enumerate()
+		.filter(|(_, line)| !line.starts_with("# ") && !line.trim().is_empty())
+		.map(|(index, line)| {
+			let line_number = index + 1;
 			let split: Vec<&str> = line.split("::").collect();
 			match split[..] {
-				[word, score_str] => Some((word, score_str)),
-				// ignore errored lines for now
-				_ => None,
+				[phrase, _, ..] if phrase.trim().is_empty() => Err(WordListError::EmptyPhrase {
+					source: source.to_string(),
+					line: line_number,
+				}),
+				[phrase, score_str, ref extra @ ..] if extra.len() <= 3 => {
+					let score = match score_str.trim().parse::<u8>() {
+						Ok(score) if score <= 100 => score,
+						_ => {
+							return Err(WordListError::BadScore {
+								source: source.to_string(),
+								line: line_number,
+								score: score_str.to_string(),
+							})
+						}
+					};
+					let field = |index: usize| {
+						extra
+							.get(index)
+							.map(|field| field.trim())
+							.filter(|field| !field.is_empty())
+					};
+					let difficulty =
+						match field(2) {
+							Some(difficulty) => Some(difficulty.parse().map_err(|_| {
+								WordListError::BadDifficulty {
+									source: source.to_string(),
+									line: line_number,
+									difficulty: difficulty.to_string(),
+								}
+							})?),
+							None => None,
+						};
+					Ok(WordListEntry {
+						phrase: phrase.to_string(),
+						score,
+						category: field(0).map(str::to_string),
+						clue: field(1).map(str::to_string),
+						difficulty,
+					})
+				}
+				_ => Err(WordListError::BadLine {
+					source: source.to_string(),
+					line: line_number,
+					content: line.to_string(),
+				}),
 			}
 		})
-		.filter_map(|(word, score_str)| {
-			// 2. Parse score into int and filter non-matching
+		.collect()
+}
 
-			let try_score_int = score_str.parse::<u8>();
-			match try_score_int {
-				Ok(score) if score >= min_score && score <= max_score => Some(word),
-				_ => None,
-			}
+/// Reads and parses a word list file, see [parse_word_list] for the format
+pub fn read_word_list(path: &Path) -> Result<Vec<WordListEntry>, WordListError> {
+	let source = path.display().to_string();
+	match fs::read_to_string(path) {
+		Ok(contents) => parse_word_list(&source, &contents),
+		Err(error) => Err(WordListError::Unreadable { source, error }),
+	}
+}
+
+/// The huge list of words and phrases that is bundled with the game. It is
+/// parsed by a test, so a broken line fails the tests instead of the game.
+pub fn builtin_word_list() -> Vec<WordListEntry> {
+	parse_word_list(GIANT_CROSSWORD_WORD_LIST_NAME, GIANT_CROSSWORD_WORD_LIST)
+		.expect("the built-in word list is valid")
+}
+
+/// A seed for [get_filtered_word_list], for when players didn't pick one
+pub fn random_seed() -> u64 {
+	rand::random()
+}
+
+/// Picks all entries of the list that match the criteria and shuffles them.
+/// Phrases that are in the list multiple times are only picked once.
+///
+/// The same list, criteria and `seed` always lead to the same order, on any
+/// platform.
+pub fn get_filtered_word_list(
+	word_list: &[WordListEntry],
+	filter: &WordFilter,
+	alphabet: &Alphabet,
+	seed: u64,
+) -> Vec<WordListEntry> {
+	let WordFilter {
+		min_length,
+		max_length,
+		min_score,
+		max_score,
+		ref category,
+	} = *filter;
+	let mut seen: HashSet<&str> = HashSet::new();
+	let filtered: Vec<&WordListEntry> = word_list
+		.iter()
+		.filter(|entry| {
+			// 1. Filter non-matching scores and categories
+
+			entry.score >= min_score
+				&& entry.score <= max_score
+				&& match category {
+					Some(category) => entry.is_in_category(category),
+					None => true,
+				}
 		})
-		.filter(|word| {
-			// 4. Parse length of alphabetical chars
+		.filter(|entry| {
+			// 2. Parse length of alphabetical chars
 
-			let try_word_len: Result<u8, _> = word
+			let try_word_len: Result<u8, _> = entry
+				.phrase
 				.chars()
 				.filter(|x| x.is_alphabetic())
 				.count()
@@ -44,10 +208,59 @@ pub fn get_filtered_word_list(
 				_ => false,
 			}
 		})
+		.filter(|entry| {
+			// 3. Only keep words that can be guessed with the alphabet
+
+			alphabet.can_spell(&entry.phrase)
+		})
+		.filter(|entry| {
+			// 4. Skip duplicates from merged lists
+
+			seen.insert(&entry.phrase)
+		})
 		.collect();
 
-	filtered
-		.choose_multiple(&mut rand::thread_rng(), filtered.len())
-		.cloned()
-		.collect()
+	// A Fisher-Yates shuffle with `u64` draws. The shuffles of `SliceRandom`
+	// draw `usize` indices, which differ between 32 and 64 bit targets.
+	let mut rng = ChaCha8Rng::seed_from_u64(seed);
+	let mut shuffled: Vec<WordListEntry> = filtered.into_iter().cloned().collect();
+	for index in (1..shuffled.len()).rev() {
+		let other = rng.gen_range(0..=index as u64) as usize;
+		shuffled.swap(index, other);
+	}
+	shuffled
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn builtin_word_list_parses() {
+		let entries = parse_word_list(GIANT_CROSSWORD_WORD_LIST_NAME, GIANT_CROSSWORD_WORD_LIST)
+			.expect("the built-in word list is valid");
+
+		assert!(!entries.is_empty());
+	}
+
+	#[test]
+	fn seeded_order_is_stable() {
+		let list =
+			parse_word_list("test", "one::50\ntwo::50\nthree::50\nfour::50\nfive::50").unwrap();
+		let filter = WordFilter {
+			min_length: 0,
+			max_length: 50,
+			min_score: 0,
+			max_score: 100,
+			category: None,
+		};
+
+		let words: Vec<String> = get_filtered_word_list(&list, &filter, &Alphabet::default(), 42)
+			.into_iter()
+			.map(|entry| entry.phrase)
+			.collect();
+
+		// the same on every platform, seeds that are shared have to keep working
+		assert_eq!(words, ["five", "three", "one", "two", "four"]);
+	}
 }